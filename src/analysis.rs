@@ -0,0 +1,334 @@
+//! Detection of still lifes, oscillators and spaceships
+
+use std::collections::HashMap;
+
+use crate::{Simulation, Universe};
+
+/// The kind of cycle a universe settles into
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CycleKind {
+    /// Every cell has died
+    Extinct,
+    /// The cells no longer change
+    StillLife,
+    /// The cells return to the same state after the period
+    Oscillator,
+    /// The cells return to the same shape after the period, moved by
+    /// `(rows, columns)` cells
+    Spaceship { displacement: (i64, i64) },
+}
+
+/// A repeating cycle found by [`Universe::find_cycle`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The generation of the first state that repeats
+    pub start: u64,
+    /// The number of generations between repeats
+    pub period: u64,
+    /// Whether the cycle is a still life, oscillator or spaceship
+    pub kind: CycleKind,
+}
+
+/// The cell states of a universe cropped to the bounding box of the cells
+/// that are not dead, so that translated copies of a pattern compare equal
+///
+/// Along an axis whose edges are joined without a twist, the box may wrap
+/// around the edge, so a pattern crossing that edge matches its unsplit
+/// copies. Patterns reaching across a whole wrapping axis, or across edges
+/// joined with a twist or shift, are cropped in place.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Shape {
+    width: usize,
+    states: Vec<u8>,
+}
+
+impl Shape {
+    /// Crops the cell states of a universe to their bounding box
+    ///
+    /// # Returns
+    ///
+    /// The shape with the (row, col) position of its top left corner
+    fn from_universe(universe: &Universe) -> (Shape, (i64, i64)) {
+        let (width, height) = (universe.width() as usize, universe.height() as usize);
+        let states = universe.cell_states();
+        let (mut rows, mut columns) = (vec![false; height], vec![false; width]);
+        for (index, _) in states.iter().enumerate().filter(|(_, &state)| state != 0) {
+            rows[index / width] = true;
+            columns[index % width] = true;
+        }
+
+        let (wrap_rows, wrap_columns) = universe.topology().plain_wraps();
+        let (Some((row_starts, crop_height)), Some((col_starts, crop_width))) =
+            (crop(&rows, wrap_rows), crop(&columns, wrap_columns))
+        else {
+            let empty = Shape {
+                width: 0,
+                states: Vec::new(),
+            };
+            return (empty, (0, 0));
+        };
+
+        // Ties between equally long gaps are broken by the smallest shape,
+        // which does not depend on where the pattern lies
+        let mut best: Option<(Shape, (i64, i64))> = None;
+        for &top in &row_starts {
+            for &left in &col_starts {
+                let mut cropped = Vec::with_capacity(crop_height * crop_width);
+                for row in 0..crop_height {
+                    let row = (top + row) % height;
+                    for col in 0..crop_width {
+                        cropped.push(states[row * width + (left + col) % width]);
+                    }
+                }
+                let shape = Shape {
+                    width: crop_width,
+                    states: cropped,
+                };
+                if best.as_ref().is_none_or(|(best, _)| shape < *best) {
+                    best = Some((shape, (top as i64, left as i64)));
+                }
+            }
+        }
+        best.expect("every axis has a crop start")
+    }
+}
+
+/// Finds where the crop of an axis of a universe can start and how long it
+/// is, from the lines of the axis that have cells that are not dead
+///
+/// The crop of an axis that wraps can start after any of its longest runs
+/// of empty lines, counting the runs that wrap around the edge.
+///
+/// # Returns
+///
+/// The lines the crop can start at and its length, or `None` if no line is
+/// occupied
+fn crop(occupied: &[bool], wraps: bool) -> Option<(Vec<usize>, usize)> {
+    let first = occupied.iter().position(|&line| line)?;
+    let last = occupied.iter().rposition(|&line| line)?;
+    let size = occupied.len();
+    if !wraps || occupied.iter().all(|&line| line) {
+        return Some((vec![first], last - first + 1));
+    }
+
+    // The empty runs, each ending just before an occupied line
+    let mut starts = Vec::new();
+    let mut longest = 0;
+    let mut run = size - 1 - last;
+    for (line, &is_occupied) in occupied.iter().enumerate().chain([(size, &true)]) {
+        if !is_occupied {
+            run += 1;
+            continue;
+        }
+        if line == size {
+            break;
+        }
+        if run > longest {
+            longest = run;
+            starts.clear();
+        }
+        if run == longest {
+            starts.push(line);
+        }
+        run = 0;
+    }
+    Some((starts, size - longest))
+}
+
+/// Returns how far a position moved along an axis, taking the shortest way
+/// around an axis that wraps
+fn moved(from: i64, to: i64, size: u32, wraps: bool) -> i64 {
+    let difference = to - from;
+    if !wraps {
+        return difference;
+    }
+    let size = size as i64;
+    let difference = difference.rem_euclid(size);
+    if difference > size / 2 {
+        difference - size
+    } else {
+        difference
+    }
+}
+
+/// Runs a copy of a universe forward until one of its states repeats
+///
+/// # Arguments
+///
+/// * `universe` - The universe to analyse, which is left unchanged
+/// * `max_generations` - The number of generations to run before giving up
+pub(crate) fn find_cycle(universe: &Universe, max_generations: u64) -> Option<Cycle> {
+    let mut universe = universe.clone();
+    let mut seen: HashMap<Shape, (u64, (i64, i64))> = HashMap::new();
+
+    for _ in 0..=max_generations {
+        let generation = universe.generation();
+        let (shape, position) = Shape::from_universe(&universe);
+
+        if let Some(&(start, start_position)) = seen.get(&shape) {
+            let (wrap_rows, wrap_columns) = universe.topology().plain_wraps();
+            let displacement = (
+                moved(start_position.0, position.0, universe.height(), wrap_rows),
+                moved(start_position.1, position.1, universe.width(), wrap_columns),
+            );
+            let period = generation - start;
+            let kind = if shape.states.is_empty() {
+                CycleKind::Extinct
+            } else if displacement != (0, 0) {
+                CycleKind::Spaceship { displacement }
+            } else if period == 1 {
+                CycleKind::StillLife
+            } else {
+                CycleKind::Oscillator
+            };
+
+            return Some(Cycle {
+                start,
+                period,
+                kind,
+            });
+        }
+
+        seen.insert(shape, (generation, position));
+        universe.tick();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rule, Topology, Transform};
+
+    #[test]
+    fn test_still_life() {
+        let universe = Universe::new_with_pattern(10, 10, vec![(4, 4), (4, 5), (5, 4), (5, 5)]);
+        assert_eq!(
+            universe.find_cycle(10),
+            Some(Cycle {
+                start: 0,
+                period: 1,
+                kind: CycleKind::StillLife
+            })
+        );
+    }
+
+    #[test]
+    fn test_extinct() {
+        let universe = Universe::new_with_pattern(10, 10, vec![(4, 4), (4, 5)]);
+        assert_eq!(
+            universe.find_cycle(10),
+            Some(Cycle {
+                start: 1,
+                period: 1,
+                kind: CycleKind::Extinct
+            })
+        );
+    }
+
+    #[test]
+    fn test_glider_is_spaceship() {
+        let mut universe = Universe::new_empty(32, 32);
+        universe.set_topology(Topology::Plane);
+        universe.draw_glider(16, 16, Transform::Identity, 0);
+
+        // The glider heads south east, one cell every four generations
+        assert_eq!(
+            universe.find_cycle(10),
+            Some(Cycle {
+                start: 0,
+                period: 4,
+                kind: CycleKind::Spaceship {
+                    displacement: (1, 1)
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_gliders_head_the_same_way_in_every_api() {
+        // Turned around, both gliders head north-west
+        let mut drawn = Universe::new_empty(32, 32);
+        drawn.draw_glider(16, 16, Transform::Rotate180, 0);
+        let mut named = Universe::new_empty(32, 32);
+        named
+            .draw_pattern("glider", 16, 16, Transform::Rotate180, 0)
+            .unwrap();
+
+        for universe in [drawn, named] {
+            assert_eq!(
+                universe.find_cycle(10),
+                Some(Cycle {
+                    start: 0,
+                    period: 4,
+                    kind: CycleKind::Spaceship {
+                        displacement: (-1, -1)
+                    }
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_glider_crossing_wrapped_edges() {
+        // The glider starts split over the four corners of the torus
+        let mut universe = Universe::new_empty(16, 16);
+        universe.draw_glider(0, 0, Transform::Identity, 0);
+        assert_eq!(
+            universe.find_cycle(10),
+            Some(Cycle {
+                start: 0,
+                period: 4,
+                kind: CycleKind::Spaceship {
+                    displacement: (1, 1)
+                }
+            })
+        );
+
+        // Columns wrap on a Klein bottle, but rows wrap with a twist
+        universe.set_topology(Topology::KleinBottle);
+        universe.clear();
+        universe.draw_glider(8, 0, Transform::Identity, 0);
+        assert_eq!(universe.find_cycle(10).map(|cycle| cycle.start), Some(0));
+    }
+
+    #[test]
+    fn test_crop_wrapped_axis() {
+        let lines = |occupied: &str| occupied.chars().map(|c| c == 'o').collect::<Vec<_>>();
+        assert_eq!(crop(&lines("o..oo."), false), Some((vec![0], 5)));
+        assert_eq!(crop(&lines("o..oo."), true), Some((vec![3], 4)));
+        assert_eq!(crop(&lines(".o..o."), true), Some((vec![1, 4], 4)));
+        assert_eq!(crop(&lines("ooo"), true), Some((vec![0], 3)));
+        assert_eq!(crop(&lines("..."), true), None);
+    }
+
+    #[test]
+    fn test_generations_spaceship() {
+        // In Brian's Brain, two live cells followed by two dying cells
+        // move one cell away from the dying cells every generation
+        let mut universe = Universe::with_rule(16, 16, Rule::parse("B2/S/C3").unwrap());
+        universe.set_cells(&[(8, 7), (8, 8)]);
+        universe.set_cell_state(9, 7, 2);
+        universe.set_cell_state(9, 8, 2);
+
+        assert_eq!(
+            universe.find_cycle(10),
+            Some(Cycle {
+                start: 0,
+                period: 1,
+                kind: CycleKind::Spaceship {
+                    displacement: (-1, 0)
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_gives_up_after_max_generations() {
+        let mut universe = Universe::new_empty(20, 20);
+        universe.draw_pulsar(10, 10, Transform::Identity, 0);
+        assert_eq!(universe.find_cycle(2), None);
+        assert_eq!(universe.find_cycle(3).map(|cycle| cycle.period), Some(3));
+    }
+}
@@ -0,0 +1,415 @@
+//! The state of the viewer and its responses to keys and the mouse
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use life_rs::{LifeError, PatternFormat, Transform, Universe};
+use ratatui::crossterm::event::{
+    KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+/// The shortest and longest delays between generations while running
+const MIN_DELAY: Duration = Duration::from_millis(10);
+const MAX_DELAY: Duration = Duration::from_millis(2560);
+
+/// The most cells shown by one half block when zoomed all the way out,
+/// as a power of two
+const MAX_ZOOM: u32 = 5;
+
+/// The number of cells the view moves when panning
+const PAN_STEP: u32 = 8;
+
+/// What typed text is being collected for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Prompt {
+    Open,
+    Save,
+}
+
+/// The state of the viewer
+pub struct App {
+    pub universe: Universe,
+    /// The (row, column) of the cell under the cursor
+    pub cursor: (u32, u32),
+    /// The (row, column) of the cell at the top left of the view
+    pub view: (u32, u32),
+    /// 0 shows every cell two characters wide, and each level above shows
+    /// `2^(zoom - 1)` by `2^(zoom - 1)` cells in each half of a character
+    pub zoom: u32,
+    /// The width and height in characters of the area showing the cells,
+    /// set each time it is drawn
+    pub area: (u16, u16),
+    pub running: bool,
+    pub delay: Duration,
+    /// The prompt and the text typed so far, while a file name is entered
+    pub prompt: Option<(Prompt, String)>,
+    /// A message shown in the status bar until the next key
+    pub message: Option<String>,
+    pub quit: bool,
+}
+
+impl App {
+    /// Creates a paused viewer with the cursor in the middle of a universe
+    pub fn new(universe: Universe) -> App {
+        App {
+            cursor: (universe.height() / 2, universe.width() / 2),
+            universe,
+            view: (0, 0),
+            zoom: 0,
+            area: (0, 0),
+            running: false,
+            delay: Duration::from_millis(160),
+            prompt: None,
+            message: None,
+            quit: false,
+        }
+    }
+
+    /// Returns the number of cells shown by one character, across and down
+    pub fn cells_per_char(&self) -> (u32, u32) {
+        match self.zoom {
+            0 => (1, 1),
+            zoom => {
+                let scale = 1 << (zoom - 1);
+                (scale, 2 * scale)
+            }
+        }
+    }
+
+    /// Returns the number of cells in the view, across and down
+    pub fn visible_cells(&self) -> (u32, u32) {
+        let (width, height) = (self.area.0 as u32, self.area.1 as u32);
+        match self.zoom {
+            0 => (width / 2, height),
+            _ => {
+                let (across, down) = self.cells_per_char();
+                (width * across, height * down)
+            }
+        }
+    }
+
+    /// Returns the (row, column) of the cell drawn at a character of the
+    /// view, or `None` if it lies past the edge of the universe
+    pub fn cell_at(&self, x: u16, y: u16) -> Option<(u32, u32)> {
+        let (x, y) = (x as u32, y as u32);
+        let (row, column) = match self.zoom {
+            0 => (self.view.0 + y, self.view.1 + x / 2),
+            _ => {
+                let (across, down) = self.cells_per_char();
+                (self.view.0 + y * down, self.view.1 + x * across)
+            }
+        };
+        (row < self.universe.height() && column < self.universe.width()).then_some((row, column))
+    }
+
+    /// Advances the universe while running
+    pub fn tick(&mut self) {
+        if self.running {
+            self.universe.tick();
+        }
+    }
+
+    /// Responds to a key press
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if let Some((prompt, mut text)) = self.prompt.take() {
+            match key.code {
+                KeyCode::Enter => self.finish_prompt(prompt, &text),
+                KeyCode::Esc => {}
+                KeyCode::Backspace => {
+                    text.pop();
+                    self.prompt = Some((prompt, text));
+                }
+                KeyCode::Char(character) => {
+                    text.push(character);
+                    self.prompt = Some((prompt, text));
+                }
+                _ => self.prompt = Some((prompt, text)),
+            }
+            return;
+        }
+
+        self.message = None;
+        let (row, column) = self.cursor;
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char(' ') => self.running = !self.running,
+            KeyCode::Char('n') | KeyCode::Char('.') => {
+                self.running = false;
+                self.universe.tick();
+            }
+            KeyCode::Char('[') => self.delay = (self.delay * 2).min(MAX_DELAY),
+            KeyCode::Char(']') => self.delay = (self.delay / 2).max(MIN_DELAY),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.zoom = self.zoom.saturating_sub(1),
+            KeyCode::Char('-') => self.zoom = (self.zoom + 1).min(MAX_ZOOM),
+            KeyCode::Up if shift => self.pan(-1, 0),
+            KeyCode::Down if shift => self.pan(1, 0),
+            KeyCode::Left if shift => self.pan(0, -1),
+            KeyCode::Right if shift => self.pan(0, 1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(0, 1),
+            KeyCode::Enter | KeyCode::Char('t') => {
+                let result = self.universe.try_toggle_cell(row, column);
+                self.show_error(result);
+            }
+            KeyCode::Char('g') => {
+                let result = self
+                    .universe
+                    .try_draw_glider(row, column, Transform::Identity, 0);
+                self.show_error(result);
+            }
+            KeyCode::Char('p') => self
+                .universe
+                .draw_pulsar(row, column, Transform::Identity, 0),
+            KeyCode::Char('b') => self
+                .universe
+                .draw_blinker(row, column, Transform::Identity, 0),
+            KeyCode::Char('B') => self
+                .universe
+                .draw_blinker(row, column, Transform::Rotate90, 0),
+            KeyCode::Char('c') => self.universe.clear(),
+            KeyCode::Char('r') => self.universe.randomize(),
+            KeyCode::Char('o') => self.prompt = Some((Prompt::Open, String::new())),
+            KeyCode::Char('s') => self.prompt = Some((Prompt::Save, String::new())),
+            _ => {}
+        }
+        self.follow_cursor();
+    }
+
+    /// Responds to a mouse event in the view, at a position relative to
+    /// its top left corner
+    pub fn handle_mouse(&mut self, mouse: MouseEvent, x: u16, y: u16) {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some((row, column)) = self.cell_at(x, y) {
+                    self.cursor = (row, column);
+                    let result = self.universe.try_toggle_cell(row, column);
+                    self.show_error(result);
+                }
+            }
+            MouseEventKind::ScrollUp => self.zoom = self.zoom.saturating_sub(1),
+            MouseEventKind::ScrollDown => self.zoom = (self.zoom + 1).min(MAX_ZOOM),
+            _ => {}
+        }
+    }
+
+    /// Shows the error of an edit that did not fit the universe in the
+    /// status bar
+    fn show_error(&mut self, result: Result<(), LifeError>) {
+        if let Err(error) = result {
+            self.message = Some(error.to_string());
+        }
+    }
+
+    /// Moves the cursor by a number of cells, stopping at the edges
+    fn move_cursor(&mut self, rows: i64, columns: i64) {
+        let (across, down) = self.cells_per_char();
+        let clamp = |value: u32, delta: i64, size: u32| {
+            (value as i64 + delta).clamp(0, size.saturating_sub(1) as i64) as u32
+        };
+        self.cursor = (
+            clamp(self.cursor.0, rows * down as i64, self.universe.height()),
+            clamp(
+                self.cursor.1,
+                columns * across as i64,
+                self.universe.width(),
+            ),
+        );
+    }
+
+    /// Moves the view and the cursor together by a number of steps
+    fn pan(&mut self, rows: i64, columns: i64) {
+        let (across, down) = self.cells_per_char();
+        let step = PAN_STEP as i64;
+        self.move_cursor(rows * step, columns * step);
+        let clamp = |value: u32, delta: i64| (value as i64 + delta).max(0) as u32;
+        self.view = (
+            clamp(self.view.0, rows * step * down as i64),
+            clamp(self.view.1, columns * step * across as i64),
+        );
+    }
+
+    /// Scrolls the view as little as possible to show the cursor
+    pub fn follow_cursor(&mut self) {
+        let (width, height) = self.visible_cells();
+        let follow = |view: u32, cursor: u32, size: u32| {
+            if size == 0 || cursor < view {
+                cursor
+            } else if cursor >= view + size {
+                cursor + 1 - size
+            } else {
+                view
+            }
+        };
+        self.view = (
+            follow(self.view.0, self.cursor.0, height),
+            follow(self.view.1, self.cursor.1, width),
+        );
+    }
+
+    /// Opens or saves a pattern file once its name has been entered
+    fn finish_prompt(&mut self, prompt: Prompt, path: &str) {
+        let result = match prompt {
+            Prompt::Open => self.open(Path::new(path)),
+            Prompt::Save => self.save(Path::new(path)),
+        };
+        self.message = Some(match result {
+            Ok(()) if prompt == Prompt::Open => format!("Opened {}", path),
+            Ok(()) => format!("Saved {}", path),
+            Err(error) => format!("{}: {}", path, error),
+        });
+    }
+
+    /// Replaces the universe with a pattern file, centered in a universe at
+    /// least as large as the current one
+    pub fn open(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let pattern = Universe::from_pattern(&fs::read_to_string(path)?)?;
+        self.universe = centered(&pattern, self.universe.width(), self.universe.height());
+        self.running = false;
+        self.cursor = (self.universe.height() / 2, self.universe.width() / 2);
+        self.follow_cursor();
+        Ok(())
+    }
+
+    /// Writes the universe to a pattern file, in the format given by its
+    /// extension or RLE otherwise
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let format = path
+            .extension()
+            .and_then(|extension| PatternFormat::from_extension(extension.to_str()?))
+            .unwrap_or(PatternFormat::Rle);
+        fs::write(path, self.universe.to_pattern(format))?;
+        Ok(())
+    }
+}
+
+/// Copies a pattern into the middle of a universe at least `width` by
+/// `height` cells, keeping its rule
+pub fn centered(pattern: &Universe, width: u32, height: u32) -> Universe {
+    let (width, height) = (width.max(pattern.width()), height.max(pattern.height()));
+    let (top, left) = (
+        (height - pattern.height()) / 2,
+        (width - pattern.width()) / 2,
+    );
+    let mut universe = Universe::with_rule(width, height, pattern.rule());
+    for row in 0..pattern.height() {
+        for column in 0..pattern.width() {
+            let state = pattern.cell_state(row, column);
+            if state != 0 {
+                universe.set_cell_state(top + row, left + column, state);
+            }
+        }
+    }
+    universe
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::KeyEventKind;
+    use ratatui::crossterm::event::KeyEventState;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent {
+            code,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new(Universe::new_empty(64, 48));
+        app.area = (40, 20);
+        app
+    }
+
+    #[test]
+    fn test_cursor_edits_cells() {
+        let mut app = app();
+        app.handle_key(key(KeyCode::Enter));
+        assert!(app.universe.is_cell_alive(24, 32));
+
+        app.handle_key(key(KeyCode::Left));
+        app.handle_key(key(KeyCode::Char('t')));
+        assert!(app.universe.is_cell_alive(24, 31));
+
+        for _ in 0..5 {
+            app.handle_key(key(KeyCode::Down));
+        }
+        app.handle_key(key(KeyCode::Char('g')));
+        assert_eq!(app.universe.get_cells().count_ones(..), 7);
+
+        app.handle_key(key(KeyCode::Char('n')));
+        assert_eq!(life_rs::Simulation::generation(&app.universe), 1);
+    }
+
+    #[test]
+    fn test_edits_outside_small_universes() {
+        let mut app = App::new(Universe::new_empty(2, 2));
+        app.handle_key(key(KeyCode::Char('g')));
+        assert!(app.message.is_some());
+        assert_eq!(app.universe.get_cells().count_ones(..), 0);
+
+        let mut app = App::new(Universe::new_empty(0, 4));
+        app.handle_key(key(KeyCode::Enter));
+        assert!(app.message.is_some());
+    }
+
+    #[test]
+    fn test_view_follows_cursor() {
+        let mut app = app();
+        app.follow_cursor();
+        // 20 cells across and 20 down are visible at the closest zoom
+        assert_eq!(app.view, (5, 13));
+
+        for _ in 0..40 {
+            app.handle_key(key(KeyCode::Right));
+        }
+        assert_eq!(app.cursor, (24, 63));
+        assert_eq!(app.view, (5, 44));
+
+        app.handle_key(key(KeyCode::Char('-')));
+        app.handle_key(key(KeyCode::Char('-')));
+        assert_eq!(app.cells_per_char(), (2, 4));
+        assert_eq!(app.cell_at(1, 1), Some((9, 46)));
+        assert_eq!(app.cell_at(39, 0), None);
+    }
+
+    #[test]
+    fn test_mouse_toggles_cell() {
+        let mut app = app();
+        let click = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 0,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        };
+        app.handle_mouse(click, 7, 3);
+        assert_eq!(app.cursor, (3, 3));
+        assert!(app.universe.is_cell_alive(3, 3));
+    }
+
+    #[test]
+    fn test_prompt_saves_and_opens() {
+        let path = std::env::temp_dir().join(format!("life-tui-{}.rle", std::process::id()));
+        let mut app = app();
+        app.universe.draw_glider(10, 10, Transform::Identity, 0);
+
+        app.handle_key(key(KeyCode::Char('s')));
+        for character in path.to_str().unwrap().chars() {
+            app.handle_key(key(KeyCode::Char(character)));
+        }
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.message, Some(format!("Saved {}", path.display())));
+
+        app.universe.clear();
+        app.open(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!((app.universe.width(), app.universe.height()), (64, 48));
+        assert_eq!(app.universe.get_cells().count_ones(..), 5);
+    }
+}
@@ -0,0 +1,116 @@
+//! The `life-tui` terminal viewer for editing and running universes
+//!
+//! The universe is drawn with block characters and edited with the cursor
+//! keys or the mouse, so it works over SSH where the browser demo cannot.
+
+mod app;
+mod ui;
+
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use life_rs::Universe;
+use ratatui::crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind,
+};
+use ratatui::crossterm::execute;
+use ratatui::DefaultTerminal;
+
+use crate::app::App;
+
+#[derive(Parser)]
+#[command(
+    name = "life-tui",
+    version,
+    about = "View and edit Life-like universes in the terminal"
+)]
+struct Cli {
+    /// A pattern file to open, in any supported format
+    pattern: Option<PathBuf>,
+    /// The width of the universe, enlarged to fit the pattern
+    #[arg(long, default_value_t = 128)]
+    width: u32,
+    /// The height of the universe, enlarged to fit the pattern
+    #[arg(long, default_value_t = 128)]
+    height: u32,
+    /// A rulestring to run instead of the pattern's rule
+    #[arg(short, long)]
+    rule: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut app = match Universe::try_new_empty(cli.width, cli.height) {
+        Ok(universe) => App::new(universe),
+        Err(error) => {
+            eprintln!("life-tui: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(path) = &cli.pattern {
+        if let Err(error) = app.open(path) {
+            eprintln!("life-tui: {}: {}", path.display(), error);
+            return ExitCode::FAILURE;
+        }
+    }
+    if let Some(rule) = &cli.rule {
+        if let Err(error) = app.universe.set_rule(rule) {
+            eprintln!("life-tui: {}", error);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let mut terminal = ratatui::init();
+    let result =
+        execute!(io::stdout(), EnableMouseCapture).and_then(|()| run(&mut terminal, &mut app));
+    let _ = execute!(io::stdout(), DisableMouseCapture);
+    ratatui::restore();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("life-tui: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Draws the viewer and handles events until it is closed
+fn run(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    let mut last_tick = Instant::now();
+    while !app.quit {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        let timeout = if app.running {
+            app.delay.saturating_sub(last_tick.elapsed())
+        } else {
+            Duration::from_millis(250)
+        };
+        if event::poll(timeout)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => app.handle_key(key),
+                Event::Mouse(mouse) => {
+                    let (cells, _, _) = ui::layout(terminal.get_frame().area());
+                    if let (Some(x), Some(y)) = (
+                        mouse.column.checked_sub(cells.x),
+                        mouse.row.checked_sub(cells.y),
+                    ) {
+                        if x < cells.width && y < cells.height {
+                            app.handle_mouse(mouse, x, y);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if last_tick.elapsed() >= app.delay {
+            app.tick();
+            last_tick = Instant::now();
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,188 @@
+//! Drawing the viewer
+
+use life_rs::Simulation;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::Frame;
+
+use crate::app::{App, Prompt};
+
+/// The keys listed below the cells
+const HELP: &str = "space play  n step  [ ] speed  +/- zoom  arrows move  shift+arrows pan  \
+                    enter toggle  g/p/b/B stamp  c clear  r random  o open  s save  q quit";
+
+/// Splits the terminal into the cells, the status line and the help line
+///
+/// # Returns
+///
+/// The area inside the border around the cells, along with the status and
+/// help lines
+pub fn layout(area: Rect) -> (Rect, Rect, Rect) {
+    let [cells, status, help] = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(area);
+    (Block::bordered().inner(cells), status, help)
+}
+
+/// Draws the viewer into a frame
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let (cells, status, help) = layout(frame.area());
+    app.area = (cells.width, cells.height);
+    app.follow_cursor();
+
+    let title = format!(
+        " {}x{} {} ",
+        app.universe.width(),
+        app.universe.height(),
+        app.universe.rule()
+    );
+    frame.render_widget(
+        Block::bordered().title(title),
+        Rect {
+            x: cells.x - 1,
+            y: cells.y - 1,
+            width: cells.width + 2,
+            height: cells.height + 2,
+        },
+    );
+    frame.render_widget(Paragraph::new(cell_lines(app)), cells);
+    frame.render_widget(Paragraph::new(status_line(app)), status);
+    frame.render_widget(
+        Paragraph::new(HELP).style(Style::default().fg(Color::DarkGray)),
+        help,
+    );
+}
+
+/// Returns the lines of characters showing the cells in view
+fn cell_lines(app: &App) -> Vec<Line<'static>> {
+    let (width, height) = app.area;
+    let cursor_style = Style::default().bg(Color::Yellow).fg(Color::Black);
+
+    (0..height)
+        .map(|y| {
+            let spans = (0..width).map(|x| {
+                let (symbol, under_cursor) = if app.zoom == 0 {
+                    closest_symbol(app, x, y)
+                } else {
+                    half_block_symbol(app, x, y)
+                };
+                if under_cursor {
+                    Span::styled(symbol, cursor_style)
+                } else {
+                    Span::raw(symbol)
+                }
+            });
+            Line::from(spans.collect::<Vec<_>>())
+        })
+        .collect()
+}
+
+/// Returns the symbol of a character at the closest zoom, where every cell
+/// is two characters wide, and whether the cursor is on its cell
+fn closest_symbol(app: &App, x: u16, y: u16) -> (&'static str, bool) {
+    match app.cell_at(x, y) {
+        Some(cell) => {
+            let symbol = match app.universe.cell_state(cell.0, cell.1) {
+                0 => " ",
+                1 => "█",
+                _ => "▒",
+            };
+            (symbol, cell == app.cursor)
+        }
+        None => ("·", false),
+    }
+}
+
+/// Returns the half block symbol of a character showing two blocks of
+/// cells, each drawn if any of its cells is not dead, and whether the
+/// cursor is in either block
+fn half_block_symbol(app: &App, x: u16, y: u16) -> (&'static str, bool) {
+    let Some((top, left)) = app.cell_at(x, y) else {
+        return ("·", false);
+    };
+    let (across, down) = app.cells_per_char();
+    let universe = &app.universe;
+    let any_alive = |top: u32| {
+        let rows = top..(top + down / 2).min(universe.height());
+        rows.into_iter().any(|row| {
+            (left..(left + across).min(universe.width()))
+                .any(|column| universe.cell_state(row, column) != 0)
+        })
+    };
+
+    let symbol = match (any_alive(top), any_alive(top + down / 2)) {
+        (false, false) => " ",
+        (true, false) => "▀",
+        (false, true) => "▄",
+        (true, true) => "█",
+    };
+    let (row, column) = app.cursor;
+    let under_cursor = (top..top + down).contains(&row) && (left..left + across).contains(&column);
+    (symbol, under_cursor)
+}
+
+/// Returns the status line, or the prompt while a file name is entered
+fn status_line(app: &App) -> Line<'static> {
+    if let Some((prompt, text)) = &app.prompt {
+        let label = match prompt {
+            Prompt::Open => "Open pattern: ",
+            Prompt::Save => "Save pattern as: ",
+        };
+        return Line::from(vec![
+            Span::styled(label, Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(text.clone()),
+            Span::styled(" ", Style::default().bg(Color::White)),
+        ]);
+    }
+
+    let state = if app.running { "running" } else { "paused" };
+    let mut status = format!(
+        "generation {}  population {}  {}  {} ms/gen  cursor {},{}",
+        app.universe.generation(),
+        app.universe.population(),
+        state,
+        app.delay.as_millis(),
+        app.cursor.0,
+        app.cursor.1
+    );
+    if let Some(message) = &app.message {
+        status.push_str("  ");
+        status.push_str(message);
+    }
+    Line::from(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use life_rs::Universe;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    /// Draws a viewer and returns the characters of the first row of cells
+    fn first_row(app: &mut App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(12, 8)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (1..11).map(|x| buffer[(x, 1)].symbol()).collect()
+    }
+
+    #[test]
+    fn test_draw_zoom_levels() {
+        let mut universe = Universe::new_empty(8, 8);
+        universe.set_cells(&[(0, 0), (1, 1), (0, 3)]);
+        let mut app = App::new(universe);
+        app.cursor = (0, 4);
+
+        assert_eq!(first_row(&mut app), "██    ██  ");
+        app.zoom = 1;
+        assert_eq!(first_row(&mut app), "▀▄ ▀    ··");
+        app.zoom = 2;
+        assert_eq!(first_row(&mut app), "▀▀  ······");
+    }
+}
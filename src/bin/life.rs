@@ -0,0 +1,392 @@
+//! The `life` command-line tool for running, converting, inspecting and
+//! rendering pattern files
+//!
+//! Patterns are read from a file, or from standard input when the path is
+//! `-`, and their format is detected from their contents. Results are
+//! written to standard output unless an output file is given.
+
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use life_rs::{
+    Color, CycleKind, HashLife, ImageOptions, PasteMode, PatternFormat, Simulation, Topology,
+    Transform, Universe,
+};
+
+/// The largest padding added by default around patterns stepped on a
+/// bounded grid
+const MAX_PADDING: u32 = 1024;
+
+#[derive(Parser)]
+#[command(
+    name = "life",
+    version,
+    about = "Run, convert and render Life-like patterns"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Step a pattern a number of generations and write the result
+    Run {
+        /// The pattern file, or `-` for standard input
+        input: PathBuf,
+        /// The number of generations to step
+        #[arg(short, long)]
+        generations: u64,
+        /// A rulestring replacing the pattern's rule
+        #[arg(short, long)]
+        rule: Option<String>,
+        /// The dead cells added around the pattern for rules HashLife
+        /// cannot run, which are stepped on a bounded grid. Defaults to as
+        /// far as the pattern can grow, up to 1024 cells
+        #[arg(long)]
+        padding: Option<u32>,
+        #[command(flatten)]
+        output: PatternOutput,
+    },
+    /// Convert a pattern to another format
+    Convert {
+        /// The pattern file, or `-` for standard input
+        input: PathBuf,
+        #[command(flatten)]
+        output: PatternOutput,
+    },
+    /// Print the rule, population, bounding box and period of a pattern
+    Info {
+        /// The pattern file, or `-` for standard input
+        input: PathBuf,
+        /// The number of generations to search for a cycle
+        #[arg(long, default_value_t = 1000)]
+        max_generations: u64,
+        /// The dead cells added around the pattern while searching for a
+        /// cycle
+        #[arg(long, default_value_t = 64)]
+        padding: u32,
+    },
+    /// Render a pattern as text or an image
+    Render {
+        /// The pattern file, or `-` for standard input
+        input: PathBuf,
+        /// The file to write, or standard output if not given
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// The output format, taken from the output file's extension if not
+        /// given
+        #[arg(short, long, value_enum)]
+        format: Option<ImageFormat>,
+        /// The width and height of each cell in pixels
+        #[arg(long, default_value_t = 8)]
+        cell_size: u32,
+        /// Draw gridlines between cells
+        #[arg(long)]
+        grid: bool,
+    },
+}
+
+#[derive(Args)]
+struct PatternOutput {
+    /// The file to write, or standard output if not given
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// The output format, taken from the output file's extension if not
+    /// given, or RLE otherwise
+    #[arg(short, long, value_enum)]
+    format: Option<FormatArg>,
+}
+
+/// The pattern formats that can be written
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum FormatArg {
+    Rle,
+    Plaintext,
+    Life105,
+    Life106,
+    Macrocell,
+}
+
+impl From<FormatArg> for PatternFormat {
+    fn from(format: FormatArg) -> Self {
+        match format {
+            FormatArg::Rle => PatternFormat::Rle,
+            FormatArg::Plaintext => PatternFormat::Plaintext,
+            FormatArg::Life105 => PatternFormat::Life105,
+            FormatArg::Life106 => PatternFormat::Life106,
+            FormatArg::Macrocell => PatternFormat::Macrocell,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ImageFormat {
+    Text,
+    Svg,
+    Png,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("life: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Run {
+            input,
+            generations,
+            rule,
+            padding,
+            output,
+        } => {
+            let mut universe = load(&input)?;
+            if let Some(rule) = rule {
+                universe.set_rule(&rule)?;
+            }
+            let format = output.format();
+
+            if HashLife::supports(&universe.rule()) {
+                let mut hashlife = HashLife::from_universe(&universe);
+                hashlife.step(generations);
+                if format == PatternFormat::Macrocell {
+                    return write_output(
+                        output.output.as_deref(),
+                        hashlife.to_macrocell().as_bytes(),
+                    );
+                }
+                universe = match hashlife.bounding_box() {
+                    Some((top, left, bottom, right)) => {
+                        let too_large = "the result is too large for a universe, use -f macrocell";
+                        let width = u32::try_from(right - left + 1).map_err(|_| too_large)?;
+                        let height = u32::try_from(bottom - top + 1).map_err(|_| too_large)?;
+                        hashlife
+                            .try_to_universe(top, left, width, height)
+                            .map_err(|_| too_large)?
+                    }
+                    None => hashlife.to_universe(0, 0, 0, 0),
+                };
+            } else {
+                // A pattern grows by at most the neighborhood range each
+                // generation
+                let range = universe.rule().neighborhood().range();
+                let padding = padding.unwrap_or_else(|| {
+                    generations
+                        .saturating_mul(range as u64)
+                        .min(MAX_PADDING as u64) as u32
+                });
+                universe = copy_region(
+                    &universe,
+                    (0, 0, universe.width(), universe.height()),
+                    padding,
+                )?;
+                universe.set_topology(Topology::Plane);
+                for _ in 0..generations {
+                    if near_border(&universe, range) {
+                        return Err(format!(
+                            "the pattern grew past the padding of {} cells, \
+                             use a larger --padding",
+                            padding
+                        )
+                        .into());
+                    }
+                    universe.tick();
+                }
+                universe = crop(&universe)?;
+            }
+            write_output(
+                output.output.as_deref(),
+                universe.to_pattern(format).as_bytes(),
+            )
+        }
+        Command::Convert { input, output } => {
+            let universe = load(&input)?;
+            let text = universe.to_pattern(output.format());
+            write_output(output.output.as_deref(), text.as_bytes())
+        }
+        Command::Info {
+            input,
+            max_generations,
+            padding,
+        } => {
+            let universe = load(&input)?;
+            println!("rule: {}", universe.rule());
+            println!("size: {}x{}", universe.width(), universe.height());
+            println!("population: {}", universe.population());
+            match universe.bounding_box() {
+                Some((top, left, bottom, right)) => println!(
+                    "bounding box: {}x{} at row {}, column {}",
+                    right - left + 1,
+                    bottom - top + 1,
+                    top,
+                    left
+                ),
+                None => println!("bounding box: none"),
+            }
+
+            let mut padded = copy_region(
+                &universe,
+                (0, 0, universe.width(), universe.height()),
+                padding,
+            )?;
+            padded.set_topology(Topology::Plane);
+            match padded.find_cycle(max_generations) {
+                Some(cycle) => {
+                    let kind = match cycle.kind {
+                        CycleKind::Extinct => "dies out".to_string(),
+                        CycleKind::StillLife => "still life".to_string(),
+                        CycleKind::Oscillator => format!("oscillator with period {}", cycle.period),
+                        CycleKind::Spaceship {
+                            displacement: (rows, columns),
+                        } => format!(
+                            "spaceship with period {}, moving ({}, {}) cells",
+                            cycle.period, rows, columns
+                        ),
+                    };
+                    println!("cycle: {} from generation {}", kind, cycle.start);
+                }
+                None => println!("cycle: none within {} generations", max_generations),
+            }
+            Ok(())
+        }
+        Command::Render {
+            input,
+            output,
+            format,
+            cell_size,
+            grid,
+        } => {
+            let universe = load(&input)?;
+            let format = format
+                .or_else(|| {
+                    let extension = output.as_deref()?.extension()?.to_str()?;
+                    ImageFormat::from_str(extension, true).ok()
+                })
+                .unwrap_or(ImageFormat::Text);
+            let options = ImageOptions {
+                cell_size,
+                gridlines: grid.then_some(Color::GRAY),
+                ..ImageOptions::default()
+            };
+
+            let bytes = match format {
+                ImageFormat::Text => universe.render().into_bytes(),
+                ImageFormat::Svg => universe.to_svg(&options, &[]).into_bytes(),
+                ImageFormat::Png => universe.to_png(&options)?,
+            };
+            write_output(output.as_deref(), &bytes)
+        }
+    }
+}
+
+impl PatternOutput {
+    /// Returns the format given on the command line or by the output file
+    fn format(&self) -> PatternFormat {
+        match (self.format, &self.output) {
+            (Some(format), _) => format.into(),
+            (None, Some(path)) => path
+                .extension()
+                .and_then(|extension| PatternFormat::from_extension(extension.to_str()?))
+                .unwrap_or(PatternFormat::Rle),
+            (None, None) => PatternFormat::Rle,
+        }
+    }
+}
+
+/// Reads a pattern file, or standard input for `-`, into a universe cropped
+/// to the pattern
+fn load(path: &Path) -> Result<Universe, Box<dyn Error>> {
+    let text = if path == Path::new("-") {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?
+    };
+
+    Ok(Universe::from_pattern(&text)?)
+}
+
+/// Writes the output to a file, or to standard output if no file is given
+fn write_output(path: Option<&Path>, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+    match path {
+        Some(path) => {
+            fs::write(path, bytes).map_err(|error| format!("{}: {}", path.display(), error))?
+        }
+        None => io::stdout().write_all(bytes)?,
+    }
+    Ok(())
+}
+
+/// Copies a region of a universe, with its rule and cell states, into a new
+/// universe with `margin` dead cells on every side
+///
+/// # Arguments
+///
+/// * `universe` - The universe to copy from
+/// * `region` - The `(row, column, width, height)` of the cells to copy
+/// * `margin` - The number of dead cells to add around the region
+///
+/// # Returns
+///
+/// The copy, or an error if it would be too large for a universe
+fn copy_region(
+    universe: &Universe,
+    region: (u32, u32, u32, u32),
+    margin: u32,
+) -> Result<Universe, Box<dyn Error>> {
+    let (top, left, width, height) = region;
+    let padded = |size: u32| {
+        margin
+            .checked_mul(2)
+            .and_then(|sides| size.checked_add(sides))
+    };
+    let (Some(padded_width), Some(padded_height)) = (padded(width), padded(height)) else {
+        return Err("the padding is too large for a universe".into());
+    };
+    let mut copy = Universe::try_with_rule(padded_width, padded_height, universe.rule())?;
+    let pattern = universe.copy_region(top, left, width, height);
+    copy.paste(
+        &pattern,
+        margin,
+        margin,
+        Transform::Identity,
+        0,
+        PasteMode::Copy,
+    )?;
+    Ok(copy)
+}
+
+/// Crops a universe to the bounding box of its live cells
+fn crop(universe: &Universe) -> Result<Universe, Box<dyn Error>> {
+    match universe.bounding_box() {
+        Some((top, left, bottom, right)) => {
+            copy_region(universe, (top, left, right - left + 1, bottom - top + 1), 0)
+        }
+        None => Ok(Universe::with_rule(0, 0, universe.rule())),
+    }
+}
+
+/// Returns whether a live cell lies within `distance` cells of the edge of
+/// a universe, so that the next generation could need cells beyond it
+fn near_border(universe: &Universe, distance: u32) -> bool {
+    match universe.bounding_box() {
+        Some((top, left, bottom, right)) => {
+            top < distance
+                || left < distance
+                || bottom.saturating_add(distance) >= universe.height()
+                || right.saturating_add(distance) >= universe.width()
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,246 @@
+//! A catalog of well known patterns, stored as RLE and looked up by name
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::formats::rle;
+use crate::Pattern;
+
+/// A named pattern of the built-in catalog
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CatalogPattern {
+    /// The name the pattern is looked up by, in lowercase with hyphens
+    pub name: &'static str,
+    /// A short description for listing the pattern
+    pub description: &'static str,
+    /// The pattern in the RLE format
+    pub rle: &'static str,
+}
+
+/// The patterns of the catalog, grouped by kind
+const CATALOG: &[CatalogPattern] = &[
+    // Still lifes
+    CatalogPattern {
+        name: "block",
+        description: "Still life of four cells",
+        rle: "x = 2, y = 2\n2o$2o!",
+    },
+    CatalogPattern {
+        name: "beehive",
+        description: "Still life of six cells",
+        rle: "x = 4, y = 3\nb2o$o2bo$b2o!",
+    },
+    CatalogPattern {
+        name: "loaf",
+        description: "Still life of seven cells",
+        rle: "x = 4, y = 4\nb2o$o2bo$bobo$2bo!",
+    },
+    CatalogPattern {
+        name: "boat",
+        description: "Still life of five cells",
+        rle: "x = 3, y = 3\n2o$obo$bo!",
+    },
+    CatalogPattern {
+        name: "tub",
+        description: "Still life of four cells",
+        rle: "x = 3, y = 3\nbo$obo$bo!",
+    },
+    // Oscillators
+    CatalogPattern {
+        name: "blinker",
+        description: "Period 2 oscillator",
+        rle: "x = 3, y = 1\n3o!",
+    },
+    CatalogPattern {
+        name: "toad",
+        description: "Period 2 oscillator",
+        rle: "x = 4, y = 2\nb3o$3o!",
+    },
+    CatalogPattern {
+        name: "beacon",
+        description: "Period 2 oscillator",
+        rle: "x = 4, y = 4\n2o$2o$2b2o$2b2o!",
+    },
+    CatalogPattern {
+        name: "pulsar",
+        description: "Period 3 oscillator",
+        rle: "x = 13, y = 13\n\
+              2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$\
+              o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+    },
+    CatalogPattern {
+        name: "pentadecathlon",
+        description: "Period 15 oscillator",
+        rle: "x = 10, y = 3\n2bo4bo$2ob4ob2o$2bo4bo!",
+    },
+    // Spaceships
+    CatalogPattern {
+        name: "glider",
+        description: "Period 4 spaceship heading south-east",
+        rle: "x = 3, y = 3\nbo$2bo$3o!",
+    },
+    CatalogPattern {
+        name: "lwss",
+        description: "Lightweight spaceship, period 4 heading west",
+        rle: "x = 5, y = 4\nbo2bo$o$o3bo$4o!",
+    },
+    CatalogPattern {
+        name: "mwss",
+        description: "Middleweight spaceship, period 4 heading west",
+        rle: "x = 6, y = 5\n3bo$bo3bo$o$o4bo$5o!",
+    },
+    CatalogPattern {
+        name: "hwss",
+        description: "Heavyweight spaceship, period 4 heading west",
+        rle: "x = 7, y = 5\n3b2o$bo4bo$o$o5bo$6o!",
+    },
+    // Guns
+    CatalogPattern {
+        name: "gosper-glider-gun",
+        description: "Gun emitting a glider every 30 generations",
+        rle: "x = 36, y = 9\n\
+              24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$\
+              2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    },
+    CatalogPattern {
+        name: "simkin-glider-gun",
+        description: "Gun emitting a glider every 120 generations",
+        rle: "x = 33, y = 21\n\
+              2o5b2o$2o5b2o2$4b2o$4b2o5$22b2ob2o$21bo5bo$21bo6bo2b2o$\
+              21b3o3bo3b2o$26bo4$20b2o$20bo$21b3o$23bo!",
+    },
+    // Methuselahs
+    CatalogPattern {
+        name: "r-pentomino",
+        description: "Methuselah stabilizing after 1103 generations",
+        rle: "x = 3, y = 3\nb2o$2o$bo!",
+    },
+    CatalogPattern {
+        name: "acorn",
+        description: "Methuselah stabilizing after 5206 generations",
+        rle: "x = 7, y = 3\nbo$3bo$2o2b3o!",
+    },
+    CatalogPattern {
+        name: "diehard",
+        description: "Methuselah dying out after 130 generations",
+        rle: "x = 8, y = 3\n6bo$2o$bo3b3o!",
+    },
+];
+
+impl CatalogPattern {
+    /// Returns every pattern of the catalog
+    pub fn all() -> &'static [CatalogPattern] {
+        CATALOG
+    }
+
+    /// Finds a pattern of the catalog by its name, ignoring case
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the pattern, such as `"gosper-glider-gun"`
+    pub fn find(name: &str) -> Option<&'static CatalogPattern> {
+        CATALOG
+            .iter()
+            .find(|pattern| pattern.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the cells of the pattern
+    pub fn pattern(&self) -> Pattern {
+        let universe = rle::parse(self.rle).expect("catalog patterns are valid RLE");
+        universe.copy_region(0, 0, universe.width(), universe.height())
+    }
+}
+
+/// Returns the names of the patterns in the catalog, for listing them in a
+/// picker
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn pattern_names() -> js_sys::Array {
+    CATALOG
+        .iter()
+        .map(|pattern| wasm_bindgen::JsValue::from_str(pattern.name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CycleKind, PasteMode, Simulation, Topology, Transform, Universe};
+
+    /// Returns a universe on a plane with the pattern in its center
+    fn padded(name: &str, margin: u32) -> Universe {
+        let pattern = CatalogPattern::find(name).unwrap().pattern();
+        let mut universe =
+            Universe::new_empty(pattern.width() + 2 * margin, pattern.height() + 2 * margin);
+        universe.set_topology(Topology::Plane);
+        universe
+            .paste(
+                &pattern,
+                margin,
+                margin,
+                Transform::Identity,
+                0,
+                PasteMode::Copy,
+            )
+            .unwrap();
+        universe
+    }
+
+    #[test]
+    fn test_catalog_names_are_unique() {
+        for (i, pattern) in CATALOG.iter().enumerate() {
+            assert!(CATALOG[..i].iter().all(|other| other.name != pattern.name));
+            assert!(pattern.pattern().population() > 0, "{}", pattern.name);
+        }
+        assert_eq!(CatalogPattern::find("LWSS").unwrap().name, "lwss");
+        assert!(CatalogPattern::find("unknown").is_none());
+    }
+
+    #[test]
+    fn test_catalog_cycles() {
+        let spaceship = |rows, columns| CycleKind::Spaceship {
+            displacement: (rows, columns),
+        };
+        let expected = [
+            ("block", CycleKind::StillLife, 1),
+            ("beehive", CycleKind::StillLife, 1),
+            ("loaf", CycleKind::StillLife, 1),
+            ("boat", CycleKind::StillLife, 1),
+            ("tub", CycleKind::StillLife, 1),
+            ("blinker", CycleKind::Oscillator, 2),
+            ("toad", CycleKind::Oscillator, 2),
+            ("beacon", CycleKind::Oscillator, 2),
+            ("pulsar", CycleKind::Oscillator, 3),
+            ("pentadecathlon", CycleKind::Oscillator, 15),
+            ("glider", spaceship(1, 1), 4),
+            ("lwss", spaceship(0, -2), 4),
+            ("mwss", spaceship(0, -2), 4),
+            ("hwss", spaceship(0, -2), 4),
+        ];
+        for (name, kind, period) in expected {
+            let cycle = padded(name, 8).find_cycle(16).unwrap();
+            assert_eq!(
+                (cycle.start, cycle.kind, cycle.period),
+                (0, kind, period),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_catalog_guns_and_methuselahs() {
+        for (name, period) in [("gosper-glider-gun", 30), ("simkin-glider-gun", 120)] {
+            let mut universe = padded(name, 40);
+            let population = universe.population();
+            universe.step(period);
+            assert_eq!(universe.population(), population + 5, "{}", name);
+        }
+
+        let mut diehard = padded("diehard", 40);
+        diehard.step(129);
+        assert!(diehard.population() > 0);
+        diehard.step(1);
+        assert_eq!(diehard.population(), 0);
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt;
+
+use crate::{PatternParseError, RleError, Rule, RuleParseError, TopologyParseError};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+
+/// An error returned by the fallible methods of the universes
+///
+/// Errors from parsing rules, topologies and pattern files convert into a
+/// `LifeError`, so they can all be propagated with `?`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LifeError {
+    /// A cell coordinate lies outside of the universe
+    CellOutOfBounds {
+        row: u32,
+        column: u32,
+        width: u32,
+        height: u32,
+    },
+    /// A cell coordinate lies outside of the range an unbounded universe
+    /// can address
+    CoordinateOutOfRange { row: i64, column: i64 },
+    /// A universe or pattern would have more cells than can be stored
+    UniverseTooLarge { width: u32, height: u32 },
+    /// A pattern is larger than the universe it is drawn in
+    PatternTooLarge { size: u32, width: u32, height: u32 },
+    /// A pattern would be advanced more generations than it can be placed
+    /// in
+    PhaseTooLarge { phase: u32, max: u32 },
+    /// A cell state is not one of the states of the universe's rule
+    InvalidState { state: u8, states: u8 },
+    /// An entry of a JavaScript cell array is not a `[row, column]` pair
+    /// of non-negative integers
+    InvalidCell { index: u32 },
+    /// No pattern of the catalog has the name
+    UnknownPattern(String),
+    /// The rule cannot be used by this kind of universe
+    UnsupportedRule(Rule),
+    /// An image of the universe would have no pixels, because of a zero
+    /// cell size or a region outside of the universe
+    EmptyImage,
+    /// An image of the universe would be larger than its format allows
+    ImageTooLarge { width: u32, height: u32 },
+    /// An animation would record a frame every zero generations
+    ZeroStride,
+    /// A rulestring could not be parsed
+    Rule(RuleParseError),
+    /// A topology name could not be parsed
+    Topology(TopologyParseError),
+    /// An RLE pattern could not be parsed
+    Rle(RleError),
+    /// A plaintext or Life 1.05/1.06 pattern could not be parsed
+    Pattern(PatternParseError),
+}
+
+impl From<RuleParseError> for LifeError {
+    fn from(error: RuleParseError) -> Self {
+        LifeError::Rule(error)
+    }
+}
+
+impl From<TopologyParseError> for LifeError {
+    fn from(error: TopologyParseError) -> Self {
+        LifeError::Topology(error)
+    }
+}
+
+impl From<RleError> for LifeError {
+    fn from(error: RleError) -> Self {
+        LifeError::Rle(error)
+    }
+}
+
+impl From<PatternParseError> for LifeError {
+    fn from(error: PatternParseError) -> Self {
+        LifeError::Pattern(error)
+    }
+}
+
+impl fmt::Display for LifeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LifeError::CellOutOfBounds {
+                row,
+                column,
+                width,
+                height,
+            } => write!(
+                f,
+                "cell ({}, {}) lies outside of the {}x{} universe",
+                row, column, width, height
+            ),
+            LifeError::CoordinateOutOfRange { row, column } => write!(
+                f,
+                "cell ({}, {}) lies outside of the addressable range",
+                row, column
+            ),
+            LifeError::UniverseTooLarge { width, height } => {
+                write!(f, "a {}x{} grid has too many cells", width, height)
+            }
+            LifeError::PatternTooLarge {
+                size,
+                width,
+                height,
+            } => write!(
+                f,
+                "a {}x{} pattern does not fit in the {}x{} universe",
+                size, size, width, height
+            ),
+            LifeError::PhaseTooLarge { phase, max } => write!(
+                f,
+                "phase {} is past the last phase {} a pattern can be placed in",
+                phase, max
+            ),
+            LifeError::InvalidState { state, states } => write!(
+                f,
+                "state {} is not one of the rule's {} states",
+                state, states
+            ),
+            LifeError::InvalidCell { index } => write!(
+                f,
+                "cell {} is not a [row, column] pair of non-negative integers",
+                index
+            ),
+            LifeError::UnknownPattern(name) => write!(f, "unknown pattern '{}'", name),
+            LifeError::UnsupportedRule(rule) => write!(f, "unsupported rule {}", rule),
+            LifeError::EmptyImage => write!(f, "image has no pixels"),
+            LifeError::ImageTooLarge { width, height } => {
+                write!(f, "image of {}x{} pixels is too large", width, height)
+            }
+            LifeError::ZeroStride => write!(f, "frame stride must be positive"),
+            LifeError::Rule(error) => write!(f, "invalid rule: {}", error),
+            LifeError::Topology(error) => error.fmt(f),
+            LifeError::Rle(error) => write!(f, "invalid RLE pattern: {}", error),
+            LifeError::Pattern(error) => write!(f, "invalid pattern: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for LifeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LifeError::Rule(error) => Some(error),
+            LifeError::Topology(error) => Some(error),
+            LifeError::Rle(error) => Some(error),
+            LifeError::Pattern(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<LifeError> for JsValue {
+    fn from(error: LifeError) -> Self {
+        js_sys::Error::new(&error.to_string()).into()
+    }
+}
@@ -0,0 +1,177 @@
+//! Stepping of Larger than Life rules with summed-area tables
+//!
+//! A neighborhood of range `r` holds up to `(2r + 1)²` cells, far too many
+//! to count one at a time. Instead, the universe is copied into a grid with
+//! an `r` cell halo taken from its topology, and each entry of a summed-area
+//! table holds the number of live cells above and left of it. The live cells
+//! of any rectangle then take four lookups, so a Moore neighborhood is
+//! counted in constant time and a von Neumann neighborhood, one row of its
+//! diamond at a time, in time linear in its range.
+
+use fixedbitset::FixedBitSet;
+
+use crate::kernel::TickBuffers;
+use crate::{Neighborhood, Universe};
+
+/// Computes the next generation of a universe into `buffers.next`
+///
+/// # Arguments
+///
+/// * `universe` - The universe to step
+/// * `buffers` - Buffers reused between ticks
+pub(crate) fn step(universe: &Universe, buffers: &mut TickBuffers) {
+    let (width, height) = (universe.width() as usize, universe.height() as usize);
+    let rule = universe.rule();
+    let neighborhood = rule.neighborhood();
+    let range = neighborhood.range() as usize;
+
+    // The table has an extra row and column of zeros above and left of
+    // the padded grid
+    let (padded_width, padded_height) = (width + 2 * range, height + 2 * range);
+    let stride = padded_width + 1;
+    let sums = &mut buffers.sums;
+    sums.clear();
+    sums.resize(stride * (padded_height + 1), 0);
+
+    let cells = universe.get_cells();
+    let alive = |row: i64, col: i64| {
+        if (0..height as i64).contains(&row) && (0..width as i64).contains(&col) {
+            cells[row as usize * width + col as usize]
+        } else {
+            universe
+                .topology()
+                .wrap(universe.width(), universe.height(), row, col)
+                .is_some_and(|(row, col)| universe.is_cell_alive(row, col))
+        }
+    };
+    for padded_row in 0..padded_height {
+        let row = padded_row as i64 - range as i64;
+        let mut row_sum = 0u32;
+        for padded_col in 0..padded_width {
+            let col = padded_col as i64 - range as i64;
+            // Sums wrap around for huge universes, but the differences
+            // taken from them stay correct
+            row_sum = row_sum.wrapping_add(alive(row, col) as u32);
+            sums[(padded_row + 1) * stride + padded_col + 1] =
+                sums[padded_row * stride + padded_col + 1].wrapping_add(row_sum);
+        }
+    }
+
+    // The live cells of the padded rows `top..bottom` and columns `left..right`
+    let sums = &buffers.sums;
+    let rectangle = |top: usize, left: usize, bottom: usize, right: usize| {
+        sums[bottom * stride + right]
+            .wrapping_sub(sums[top * stride + right])
+            .wrapping_sub(sums[bottom * stride + left])
+            .wrapping_add(sums[top * stride + left])
+    };
+
+    if buffers.next.len() != width * height {
+        buffers.next = FixedBitSet::with_capacity(width * height);
+    }
+    for row in 0..height {
+        for col in 0..width {
+            // The neighborhood of the cell starts at padded (row, col)
+            let live_cells = match neighborhood {
+                Neighborhood::VonNeumann(_) => (0..=2 * range)
+                    .map(|delta_row| {
+                        let reach = range - delta_row.abs_diff(range);
+                        let padded_row = row + delta_row;
+                        rectangle(
+                            padded_row,
+                            col + range - reach,
+                            padded_row + 1,
+                            col + range + reach + 1,
+                        )
+                    })
+                    .fold(0u32, u32::wrapping_add),
+                _ => rectangle(row, col, row + 2 * range + 1, col + 2 * range + 1),
+            };
+
+            let index = row * width + col;
+            let alive = cells[index];
+            let next = rule.next_state_for_count(alive, live_cells - alive as u32);
+            buffers.next.set(index, next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rule, Topology};
+
+    /// Computes the next generation by visiting every neighbor of every cell
+    fn reference_step(universe: &Universe) -> FixedBitSet {
+        let (width, height) = (universe.width(), universe.height());
+        let neighborhood = universe.rule().neighborhood();
+        let range = neighborhood.range() as i64;
+
+        let mut next = FixedBitSet::with_capacity((width * height) as usize);
+        for row in 0..height {
+            for col in 0..width {
+                let mut live_neighbors = 0;
+                for delta_row in -range..=range {
+                    for delta_col in -range..=range {
+                        if !neighborhood.contains(delta_row, delta_col) {
+                            continue;
+                        }
+                        let neighbor = universe.topology().wrap(
+                            width,
+                            height,
+                            row as i64 + delta_row,
+                            col as i64 + delta_col,
+                        );
+                        if neighbor.is_some_and(|(row, col)| universe.is_cell_alive(row, col)) {
+                            live_neighbors += 1;
+                        }
+                    }
+                }
+                let alive = universe.is_cell_alive(row, col);
+                next.set(
+                    (row * width + col) as usize,
+                    universe.rule().next_state_for_count(alive, live_neighbors),
+                );
+            }
+        }
+        next
+    }
+
+    #[test]
+    fn test_matches_reference_step() {
+        let rules = [
+            "R2,C0,M0,S3..6,B4..5,NM",
+            "R3,C0,M1,S5..12,B6..9,NN",
+            "R5,C0,M1,S34..58,B34..45,NM",
+        ];
+        let topologies = [
+            Topology::Torus,
+            Topology::Plane,
+            Topology::KleinBottle,
+            Topology::ShiftedTorus(3),
+        ];
+
+        for (width, height) in [(1, 1), (3, 2), (17, 9), (40, 33)] {
+            for topology in topologies {
+                for rule in rules {
+                    let mut universe =
+                        Universe::with_rule(width, height, Rule::parse(rule).unwrap());
+                    universe.set_topology(topology);
+                    universe.randomize();
+
+                    let mut buffers = TickBuffers::default();
+                    step(&universe, &mut buffers);
+                    assert_eq!(
+                        buffers.next,
+                        reference_step(&universe),
+                        "{}x{} {} {}",
+                        width,
+                        height,
+                        topology,
+                        rule
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! Life 1.05 and Life 1.06 patterns
+//!
+//! Life 1.06 lists the `x y` coordinates of every live cell, one per line,
+//! after a `#Life 1.06` header. Life 1.05 stores blocks of `.` and `*` cells,
+//! each placed by a `#P x y` line giving the position of its top left cell,
+//! and may name its rule with `#N` (Conway's Game of Life) or `#R` in S/B
+//! notation. In both formats `x` is the column and `y` the row, and either
+//! may be negative.
+
+use super::PatternParseError;
+use crate::{Rule, Universe};
+
+/// The header line of a Life 1.05 pattern
+const LIFE_105_HEADER: &str = "#Life 1.05";
+
+/// The header line of a Life 1.06 pattern
+const LIFE_106_HEADER: &str = "#Life 1.06";
+
+/// The maximum length of a row of cells in a Life 1.05 block
+const LIFE_105_LINE_LENGTH: u32 = 80;
+
+/// Live cells read from a Life 1.05 pattern along with its rule
+pub(crate) struct Life105 {
+    /// The rule named by a `#N` or `#R` line, if any
+    pub rule: Option<Rule>,
+    /// (row, col) coordinates of the live cells relative to the pattern's origin
+    pub alive_cells: Vec<(i64, i64)>,
+}
+
+/// Returns the numbered lines of a pattern after checking its header
+fn lines_after_header<'a>(
+    text: &'a str,
+    header: &'static str,
+) -> Result<impl Iterator<Item = (usize, &'a str)>, PatternParseError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(number, line)| (number + 1, line.trim()))
+        .skip_while(|(_, line)| line.is_empty());
+
+    match lines.next() {
+        Some((_, first)) if first.eq_ignore_ascii_case(header) => Ok(lines),
+        _ => Err(PatternParseError::MissingHeader(header)),
+    }
+}
+
+/// Parses a pair of whitespace separated integers
+fn parse_pair(text: &str) -> Option<(i64, i64)> {
+    let mut values = text.split_whitespace().map(|value| value.parse().ok());
+    match (values.next(), values.next(), values.next()) {
+        (Some(Some(first)), Some(Some(second)), None) => Some((first, second)),
+        _ => None,
+    }
+}
+
+/// Parses a Life 1.06 pattern
+///
+/// # Arguments
+///
+/// * `text` - The contents of a Life 1.06 file
+///
+/// # Returns
+///
+/// The (row, col) coordinates of the live cells
+pub(crate) fn parse_106(text: &str) -> Result<Vec<(i64, i64)>, PatternParseError> {
+    let mut alive_cells = Vec::new();
+    for (number, line) in lines_after_header(text, LIFE_106_HEADER)? {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (x, y) = parse_pair(line).ok_or(PatternParseError::InvalidLine { line: number })?;
+        alive_cells.push((y, x));
+    }
+    Ok(alive_cells)
+}
+
+/// Writes the live cells of a universe as a Life 1.06 pattern
+///
+/// # Arguments
+///
+/// * `universe` - The universe to encode
+pub(crate) fn write_106(universe: &Universe) -> String {
+    let mut text = format!("{}\n", LIFE_106_HEADER);
+    for row in 0..universe.height() {
+        for column in 0..universe.width() {
+            if universe.is_cell_alive(row, column) {
+                text.push_str(&format!("{} {}\n", column, row));
+            }
+        }
+    }
+    text
+}
+
+/// Parses a Life 1.05 pattern
+///
+/// # Arguments
+///
+/// * `text` - The contents of a Life 1.05 file
+pub(crate) fn parse_105(text: &str) -> Result<Life105, PatternParseError> {
+    let mut rule = None;
+    let mut alive_cells = Vec::new();
+    // The row of the next line of cells, or `None` once it is past the
+    // largest coordinate
+    let (mut block_row, mut block_col) = (Some(0i64), 0i64);
+
+    for (number, line) in lines_after_header(text, LIFE_105_HEADER)? {
+        if let Some(position) = line.strip_prefix("#P") {
+            let (x, y) =
+                parse_pair(position).ok_or(PatternParseError::InvalidLine { line: number })?;
+            (block_row, block_col) = (Some(y), x);
+        } else if line == "#N" {
+            rule = Some(Rule::conway());
+        } else if let Some(rulestring) = line.strip_prefix("#R") {
+            rule = Some(Rule::parse(rulestring)?);
+        } else if line.starts_with('#') {
+            // Descriptions (`#D`) and unknown lines carry no cells
+        } else {
+            let invalid = || PatternParseError::InvalidLine { line: number };
+            let row = block_row.ok_or_else(invalid)?;
+            for (col, character) in line.chars().enumerate() {
+                match character {
+                    '.' => {}
+                    '*' => {
+                        let col = block_col.checked_add(col as i64).ok_or_else(invalid)?;
+                        alive_cells.push((row, col));
+                    }
+                    _ => {
+                        return Err(PatternParseError::InvalidCharacter {
+                            line: number,
+                            character,
+                        })
+                    }
+                }
+            }
+            block_row = row.checked_add(1);
+        }
+    }
+
+    Ok(Life105 { rule, alive_cells })
+}
+
+/// Writes the live cells of a universe as a Life 1.05 pattern
+///
+/// Wide universes are split into blocks of at most 80 columns.
+///
+/// # Arguments
+///
+/// * `universe` - The universe to encode
+pub(crate) fn write_105(universe: &Universe) -> String {
+    let mut text = format!("{}\n", LIFE_105_HEADER);
+
+    let rule = universe.rule();
+    if rule == Rule::conway() {
+        text.push_str("#N\n");
+    } else if rule.is_larger_than_life() {
+        text.push_str(&format!("#R {}\n", rule));
+    } else {
+        // Life 1.05 writes rules in S/B notation without letters
+        text.push_str(&format!(
+            "#R {}/{}",
+            rule.survival_conditions(),
+            rule.birth_conditions()
+        ));
+        if rule.states() > 2 {
+            text.push_str(&format!("/{}", rule.states()));
+        }
+        text.push_str(rule.neighborhood_suffix());
+        text.push('\n');
+    }
+
+    for first_col in (0..universe.width()).step_by(LIFE_105_LINE_LENGTH as usize) {
+        let columns = first_col..universe.width().min(first_col + LIFE_105_LINE_LENGTH);
+        let rows: Vec<String> = (0..universe.height())
+            .map(|row| {
+                let line: String = columns
+                    .clone()
+                    .map(|col| {
+                        if universe.is_cell_alive(row, col) {
+                            '*'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect();
+                line.trim_end_matches('.').to_string()
+            })
+            .collect();
+
+        let (Some(first_row), Some(last_row)) = (
+            rows.iter().position(|line| !line.is_empty()),
+            rows.iter().rposition(|line| !line.is_empty()),
+        ) else {
+            continue;
+        };
+
+        text.push_str(&format!("#P {} {}\n", first_col, first_row));
+        for line in &rows[first_row..=last_row] {
+            // An empty row is written as a single dead cell
+            text.push_str(if line.is_empty() { "." } else { line });
+            text.push('\n');
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_106() {
+        let cells = parse_106("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n").unwrap();
+        assert_eq!(cells, vec![(-1, 0), (0, 1), (1, -1), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn test_parse_106_errors() {
+        assert_eq!(
+            parse_106("0 0\n").err(),
+            Some(PatternParseError::MissingHeader(LIFE_106_HEADER))
+        );
+        assert_eq!(
+            parse_106("#Life 1.06\n0 0\n1 x\n").err(),
+            Some(PatternParseError::InvalidLine { line: 3 })
+        );
+    }
+
+    #[test]
+    fn test_parse_105_blocks() {
+        let text = "#Life 1.05
+#D Two blocks
+#R 23/36
+#P -1 -1
+.*
+*.*
+#P 5 0
+**
+";
+        let pattern = parse_105(text).unwrap();
+        assert_eq!(pattern.rule, Some(Rule::new(&[3, 6], &[2, 3])));
+        assert_eq!(
+            pattern.alive_cells,
+            vec![(-1, 0), (0, -1), (0, 1), (0, 5), (0, 6)]
+        );
+
+        // Cells past the largest coordinate are rejected
+        let invalid = |text: &str| parse_105(text).err();
+        assert_eq!(
+            invalid("#Life 1.05\n#P 9223372036854775807 0\n**\n"),
+            Some(PatternParseError::InvalidLine { line: 3 })
+        );
+        assert_eq!(
+            invalid("#Life 1.05\n#P 0 9223372036854775807\n*\n*\n"),
+            Some(PatternParseError::InvalidLine { line: 4 })
+        );
+        assert!(parse_105("#Life 1.05\n#P 0 9223372036854775807\n*\n").is_ok());
+    }
+
+    #[test]
+    fn test_write_105_round_trip() {
+        let mut universe = Universe::with_rule(100, 4, Rule::new(&[3, 6], &[2, 3]));
+        universe.set_cells(&[(1, 2), (1, 3), (3, 90)]);
+
+        let text = write_105(&universe);
+        assert!(text.contains("#R 23/36\n"));
+        assert!(text.contains("#P 0 1\n"));
+        assert!(text.contains("#P 80 3\n"));
+
+        let pattern = parse_105(&text).unwrap();
+        assert_eq!(pattern.rule, Some(universe.rule()));
+        assert_eq!(pattern.alive_cells, vec![(1, 2), (1, 3), (3, 90)]);
+    }
+}
@@ -0,0 +1,378 @@
+//! Golly macrocell (`.mc`) patterns
+//!
+//! A macrocell file stores the quadtree of a HashLife universe, so huge
+//! patterns with repeating structure stay small. After a `[M2]` header and
+//! optional `#R` (rule) and `#G` (generation) lines, every line defines a
+//! node, numbered from 1:
+//!
+//! - An 8 by 8 leaf is written row by row, with `.` for a dead cell, `*`
+//!   for a live cell and `$` to end a row, e.g. `$$..*$...*$.***$`. Dead
+//!   cells at the end of a row and empty rows at the end are left out.
+//! - A larger node is written as `k nw ne sw se`: its level `k`, covering
+//!   `2^k` by `2^k` cells, followed by the numbers of its quadrants, with 0
+//!   for an empty quadrant.
+//!
+//! The last node is the root, which is centered on the cell at `(0, 0)`.
+
+use std::collections::HashMap;
+
+use super::{PatternParseError, MAX_UNIVERSE_CELLS};
+use crate::hashlife::NodeId;
+use crate::{HashLife, Rule, Simulation, Universe};
+
+const MACROCELL_HEADER: &str = "[M2]";
+
+/// The level of the 8 by 8 leaves
+const LEAF_LEVEL: u8 = 3;
+
+/// The highest level of a root whose cells all have `i64` coordinates
+const MAX_LEVEL: u8 = 62;
+
+/// Parses a macrocell pattern into a HashLife universe
+///
+/// The rule is not checked, so the pattern may use rules that HashLife
+/// cannot advance.
+///
+/// # Arguments
+///
+/// * `text` - The contents of a `.mc` file
+pub(crate) fn parse(text: &str) -> Result<HashLife, PatternParseError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(number, line)| (number + 1, line.trim()));
+    match lines.next() {
+        Some((_, header)) if header.starts_with(MACROCELL_HEADER) => {}
+        _ => return Err(PatternParseError::MissingHeader(MACROCELL_HEADER)),
+    }
+
+    // The rule is needed to create the universe the nodes are added to
+    let mut rule = Rule::conway();
+    let mut generation = 0;
+    let mut node_lines = Vec::new();
+    for (number, line) in lines {
+        if let Some(rulestring) = line.strip_prefix("#R") {
+            rule = Rule::parse(rulestring)?;
+        } else if let Some(value) = line.strip_prefix("#G") {
+            generation = value
+                .trim()
+                .parse()
+                .map_err(|_| PatternParseError::InvalidLine { line: number })?;
+        } else if !line.is_empty() && !line.starts_with('#') {
+            node_lines.push((number, line));
+        }
+    }
+
+    let mut hashlife = HashLife::with_rule_unchecked(rule);
+    let mut nodes: Vec<NodeId> = Vec::with_capacity(node_lines.len());
+    for (number, line) in node_lines {
+        let node = if line.starts_with(['.', '*', '$']) {
+            parse_leaf(&mut hashlife, number, line)?
+        } else {
+            parse_node(&mut hashlife, number, line, &nodes)?
+        };
+        nodes.push(node);
+    }
+
+    let root = match nodes.last() {
+        Some(&root) => root,
+        None => hashlife.empty_node(LEAF_LEVEL),
+    };
+    let (level, _, _) = hashlife.node(root);
+    let half = 1i64 << (level - 1);
+    hashlife.set_root(root, (-half, -half));
+    hashlife.set_generation(generation);
+    Ok(hashlife)
+}
+
+/// Parses a macrocell pattern into a universe cropped to its live cells
+///
+/// # Arguments
+///
+/// * `text` - The contents of a `.mc` file
+pub(crate) fn parse_universe(text: &str) -> Result<Universe, PatternParseError> {
+    let hashlife = parse(text)?;
+    let Some((top, left, bottom, right)) = hashlife.bounding_box() else {
+        return Ok(Universe::with_rule(0, 0, hashlife.rule()));
+    };
+
+    let (width, height) = ((right - left + 1) as u64, (bottom - top + 1) as u64);
+    if width.saturating_mul(height) > MAX_UNIVERSE_CELLS {
+        return Err(PatternParseError::PatternTooLarge { width, height });
+    }
+    let mut universe = hashlife.to_universe(top, left, width as u32, height as u32);
+    universe.generation = hashlife.generation();
+    Ok(universe)
+}
+
+/// Parses an 8 by 8 leaf such as `$$..*$...*$.***$`
+fn parse_leaf(
+    hashlife: &mut HashLife,
+    number: usize,
+    line: &str,
+) -> Result<NodeId, PatternParseError> {
+    let mut cells = [[false; 8]; 8];
+    let (mut row, mut col) = (0, 0);
+    for character in line.chars() {
+        match character {
+            '.' => col += 1,
+            '*' if row < 8 && col < 8 => {
+                cells[row][col] = true;
+                col += 1;
+            }
+            '$' => (row, col) = (row + 1, 0),
+            '*' => return Err(PatternParseError::InvalidLine { line: number }),
+            _ => {
+                return Err(PatternParseError::InvalidCharacter {
+                    line: number,
+                    character,
+                })
+            }
+        }
+        if row > 8 || col > 8 {
+            return Err(PatternParseError::InvalidLine { line: number });
+        }
+    }
+
+    let alive = |row: i64, col: i64| cells[row as usize][col as usize];
+    Ok(hashlife.build(LEAF_LEVEL, 0, 0, &alive))
+}
+
+/// Parses a node line such as `4 0 0 0 1` from the nodes defined before it
+fn parse_node(
+    hashlife: &mut HashLife,
+    number: usize,
+    line: &str,
+    nodes: &[NodeId],
+) -> Result<NodeId, PatternParseError> {
+    let invalid = || PatternParseError::InvalidLine { line: number };
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [level, quadrants @ ..] = fields.as_slice() else {
+        return Err(invalid());
+    };
+    let level: u8 = level.parse().map_err(|_| invalid())?;
+    if quadrants.len() != 4 || !(LEAF_LEVEL + 1..=MAX_LEVEL).contains(&level) {
+        return Err(invalid());
+    }
+
+    let mut children = [0; 4];
+    for (child, quadrant) in children.iter_mut().zip(quadrants) {
+        let index: usize = quadrant.parse().map_err(|_| invalid())?;
+        *child = match index {
+            0 => hashlife.empty_node(level - 1),
+            _ => *nodes.get(index - 1).ok_or_else(invalid)?,
+        };
+        if hashlife.node(*child).0 != level - 1 {
+            return Err(invalid());
+        }
+    }
+
+    let [nw, ne, sw, se] = children;
+    Ok(hashlife.join(nw, ne, sw, se))
+}
+
+/// Writes the pattern of a HashLife universe as a macrocell pattern
+///
+/// # Arguments
+///
+/// * `hashlife` - The universe to encode
+pub(crate) fn write(hashlife: &HashLife) -> String {
+    let mut text = format!("{} (life-rs)\n#R {}\n", MACROCELL_HEADER, hashlife.rule());
+    if hashlife.generation() > 0 {
+        text.push_str(&format!("#G {}\n", hashlife.generation()));
+    }
+
+    let (root, _) = hashlife.root();
+    let mut numbers = HashMap::new();
+    write_node(hashlife, root, &mut numbers, &mut text);
+    text
+}
+
+/// Writes the live cells of a universe as a macrocell pattern
+///
+/// # Arguments
+///
+/// * `universe` - The universe to encode
+pub(crate) fn write_universe(universe: &Universe) -> String {
+    // The universe's rule is only written, so it need not be one HashLife supports
+    let mut hashlife = HashLife::with_rule_unchecked(universe.rule());
+    hashlife.load_region(universe, 0, 0, universe.width(), universe.height());
+    hashlife.set_generation(universe.generation());
+    write(&hashlife)
+}
+
+/// Writes a node after its quadrants, unless it was already written
+///
+/// # Returns
+///
+/// The number of the node, or 0 if it is empty
+fn write_node(
+    hashlife: &HashLife,
+    id: NodeId,
+    numbers: &mut HashMap<NodeId, usize>,
+    text: &mut String,
+) -> usize {
+    let (level, children, population) = hashlife.node(id);
+    if population == 0 {
+        return 0;
+    }
+    if let Some(&number) = numbers.get(&id) {
+        return number;
+    }
+
+    if level == LEAF_LEVEL {
+        let mut rows = [[false; 8]; 8];
+        hashlife.for_each_alive_in(id, (0, 0), |row, col| {
+            rows[row as usize][col as usize] = true;
+        });
+        let last_row = rows.iter().rposition(|row| row.contains(&true));
+        for row in &rows[..last_row.map_or(0, |last| last + 1)] {
+            let len = row
+                .iter()
+                .rposition(|&alive| alive)
+                .map_or(0, |last| last + 1);
+            text.extend(
+                row[..len]
+                    .iter()
+                    .map(|&alive| if alive { '*' } else { '.' }),
+            );
+            text.push('$');
+        }
+    } else {
+        let quadrants = children.map(|child| write_node(hashlife, child, numbers, text));
+        let [nw, ne, sw, se] = quadrants;
+        text.push_str(&format!("{} {} {} {} {}", level, nw, ne, sw, se));
+    }
+    text.push('\n');
+
+    let number = numbers.len() + 1;
+    numbers.insert(id, number);
+    number
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LifeError, Transform};
+
+    const GLIDER: &str = "\
+[M2] (golly 4.2)
+#R B3/S23
+#G 12
+$$..*$...*$.***$
+4 0 0 0 1
+";
+
+    #[test]
+    fn test_parse_glider() {
+        let hashlife = HashLife::from_macrocell(GLIDER).unwrap();
+        assert_eq!(hashlife.generation(), 12);
+        assert_eq!(hashlife.population(), 5);
+
+        // The root is centered on (0, 0), so its south east leaf starts there
+        for (row, col) in [(2, 2), (3, 3), (4, 1), (4, 2), (4, 3)] {
+            assert!(hashlife.is_cell_alive(row, col));
+        }
+
+        let universe = Universe::from_macrocell(GLIDER).unwrap();
+        assert_eq!((universe.width(), universe.height()), (3, 3));
+        assert_eq!(universe.to_plaintext(), ".O.\n..O\nOOO\n");
+        assert_eq!(universe.generation(), 12);
+    }
+
+    #[test]
+    fn test_round_trip_shares_nodes() {
+        const DISTANCE: i64 = 1 << 40;
+        let mut hashlife = HashLife::new();
+        let pulsar = crate::patterns::pulsar();
+        for &(row, col) in &pulsar {
+            hashlife.set_cell(row, col, true);
+            hashlife.set_cell(row + DISTANCE, col - DISTANCE, true);
+        }
+
+        let text = hashlife.to_macrocell();
+        // Empty space is written as 0 rather than as nodes, so only the
+        // nodes around the two pulsars take up lines
+        assert!(text.lines().count() < 200);
+
+        let parsed = HashLife::from_macrocell(&text).unwrap();
+        assert_eq!(parsed.population(), hashlife.population());
+        let (top, left, _, _) = parsed.bounding_box().unwrap();
+        let (old_top, old_left, _, _) = hashlife.bounding_box().unwrap();
+        for &(row, col) in &pulsar {
+            let (row, col) = (row - old_top, col - old_left);
+            assert!(parsed.is_cell_alive(top + row, left + col));
+            assert!(parsed.is_cell_alive(top + row + DISTANCE, left + col - DISTANCE));
+        }
+    }
+
+    #[test]
+    fn test_universe_round_trip() {
+        let mut universe = Universe::with_rule(20, 20, Rule::parse("B36/S23").unwrap());
+        universe.draw_glider(5, 5, Transform::Identity, 0);
+        universe.draw_blinker(15, 12, Transform::Identity, 0);
+
+        let parsed = Universe::from_macrocell(&universe.to_macrocell()).unwrap();
+        assert_eq!(parsed.rulestring(), "B36/S23");
+        assert_eq!((parsed.width(), parsed.height()), (10, 12));
+        let (top, left) = (4, 4);
+        for row in 0..universe.height() {
+            for col in 0..universe.width() {
+                let parsed_alive = parsed
+                    .try_is_cell_alive(row.wrapping_sub(top), col.wrapping_sub(left))
+                    .unwrap_or(false);
+                assert_eq!(universe.is_cell_alive(row, col), parsed_alive);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |text| parse(text).err().unwrap();
+
+        assert_eq!(
+            error("x = 3, y = 3\n"),
+            PatternParseError::MissingHeader("[M2]")
+        );
+        assert_eq!(
+            error("[M2]\n.*$\n4 0 0 0 2\n"),
+            PatternParseError::InvalidLine { line: 3 }
+        );
+        assert_eq!(
+            error("[M2]\n.*$\n5 0 0 0 1\n"),
+            PatternParseError::InvalidLine { line: 3 }
+        );
+        assert_eq!(
+            error("[M2]\n.*o$\n"),
+            PatternParseError::InvalidCharacter {
+                line: 2,
+                character: 'o'
+            }
+        );
+        assert_eq!(
+            HashLife::from_macrocell("[M2]\n#R B2/S/C3\n").err(),
+            Some(LifeError::UnsupportedRule(Rule::parse("B2/S/C3").unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_universe_too_large() {
+        // Two cells in opposite corners of a node 2^40 cells across, each
+        // at the end of a chain of nodes with a single non-empty quadrant
+        let mut text = String::from("[M2]\n*$\n$$$$$$$.......*$\n");
+        let (mut north_west, mut south_east) = (1, 2);
+        for level in 4..40 {
+            text.push_str(&format!("{} {} 0 0 0\n", level, north_west));
+            text.push_str(&format!("{} 0 0 0 {}\n", level, south_east));
+            (north_west, south_east) = (south_east + 1, south_east + 2);
+        }
+        text.push_str(&format!("40 {} 0 0 {}\n", north_west, south_east));
+
+        let hashlife = HashLife::from_macrocell(&text).unwrap();
+        assert_eq!(hashlife.population(), 2);
+        assert!(matches!(
+            Universe::from_macrocell(&text),
+            Err(PatternParseError::PatternTooLarge { .. })
+        ));
+    }
+}
@@ -0,0 +1,170 @@
+//! Readers and writers for pattern file formats
+
+use std::fmt;
+
+use crate::{Rule, RuleParseError, Universe};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+
+pub mod life;
+pub mod macrocell;
+pub mod plaintext;
+pub mod rle;
+
+/// A pattern file format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternFormat {
+    /// Run length encoded patterns (`.rle`)
+    Rle,
+    /// Plaintext patterns (`.cells`)
+    Plaintext,
+    /// Life 1.05 patterns
+    Life105,
+    /// Life 1.06 patterns
+    Life106,
+    /// Golly macrocell patterns (`.mc`)
+    Macrocell,
+}
+
+impl PatternFormat {
+    /// Detects the format of a pattern from its contents
+    ///
+    /// Patterns without a recognized header or RLE size line are taken to
+    /// be plaintext.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of a pattern file
+    pub fn detect(text: &str) -> PatternFormat {
+        let text = text.trim_start();
+        if text.starts_with("[M2]") {
+            PatternFormat::Macrocell
+        } else if text.starts_with("#Life 1.05") {
+            PatternFormat::Life105
+        } else if text.starts_with("#Life 1.06") {
+            PatternFormat::Life106
+        } else if text
+            .lines()
+            .map(str::trim_start)
+            .find(|line| !line.starts_with('#'))
+            .is_some_and(|line| line.starts_with('x'))
+        {
+            PatternFormat::Rle
+        } else {
+            PatternFormat::Plaintext
+        }
+    }
+
+    /// Returns the format of pattern files with a file extension, such as
+    /// `rle` or `mc`, or `None` if the extension is not known
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The file extension, without the leading dot
+    pub fn from_extension(extension: &str) -> Option<PatternFormat> {
+        match extension.to_ascii_lowercase().as_str() {
+            "rle" => Some(PatternFormat::Rle),
+            "cells" | "txt" => Some(PatternFormat::Plaintext),
+            "lif" | "life" => Some(PatternFormat::Life106),
+            "mc" => Some(PatternFormat::Macrocell),
+            _ => None,
+        }
+    }
+}
+
+/// The most cells of any universe, which take 128 MiB, so that patterns
+/// without dimensions and user supplied sizes cannot exhaust memory
+pub(crate) const MAX_UNIVERSE_CELLS: u64 = 1 << 30;
+
+/// Creates a universe cropped to the live cells of a pattern
+///
+/// # Arguments
+///
+/// * `rule` - The rule of the universe
+/// * `alive_cells` - (row, col) coordinates of the live cells, which may be
+///   negative
+///
+/// # Returns
+///
+/// An error if the live cells span more than [`MAX_UNIVERSE_CELLS`] cells
+pub(crate) fn cropped_universe(
+    rule: Rule,
+    alive_cells: &[(i64, i64)],
+) -> Result<Universe, PatternParseError> {
+    let rows = alive_cells.iter().map(|&(row, _)| row);
+    let cols = alive_cells.iter().map(|&(_, col)| col);
+    let (Some(top), Some(bottom), Some(left), Some(right)) = (
+        rows.clone().min(),
+        rows.max(),
+        cols.clone().min(),
+        cols.max(),
+    ) else {
+        return Ok(Universe::with_rule(0, 0, rule));
+    };
+
+    // A pattern spanning every coordinate saturates and is rejected below
+    let width = right.abs_diff(left).saturating_add(1);
+    let height = bottom.abs_diff(top).saturating_add(1);
+    if width.saturating_mul(height) > MAX_UNIVERSE_CELLS {
+        return Err(PatternParseError::PatternTooLarge { width, height });
+    }
+    let mut universe = Universe::with_rule(width as u32, height as u32, rule);
+    let offsets: Vec<_> = alive_cells
+        .iter()
+        .map(|&(row, col)| (row.wrapping_sub(top), col.wrapping_sub(left)))
+        .collect();
+    universe.draw_cells(0, 0, &offsets);
+    Ok(universe)
+}
+
+/// An error returned when a plaintext, Life 1.05/1.06 or macrocell pattern
+/// cannot be parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatternParseError {
+    /// The pattern does not start with the header its format requires
+    MissingHeader(&'static str),
+    /// A line could not be read as a coordinate pair or block position
+    InvalidLine { line: usize },
+    /// A row of cells contains a character that is not a cell
+    InvalidCharacter { line: usize, character: char },
+    /// The rule given in the pattern could not be parsed
+    InvalidRule(RuleParseError),
+    /// The live cells of the pattern span more cells than a universe can hold
+    PatternTooLarge { width: u64, height: u64 },
+}
+
+impl From<RuleParseError> for PatternParseError {
+    fn from(error: RuleParseError) -> Self {
+        PatternParseError::InvalidRule(error)
+    }
+}
+
+impl fmt::Display for PatternParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternParseError::MissingHeader(header) => {
+                write!(f, "pattern must start with a '{}' header", header)
+            }
+            PatternParseError::InvalidLine { line } => write!(f, "invalid line {}", line),
+            PatternParseError::InvalidCharacter { line, character } => {
+                write!(f, "invalid character '{}' on line {}", character, line)
+            }
+            PatternParseError::InvalidRule(error) => write!(f, "invalid rule: {}", error),
+            PatternParseError::PatternTooLarge { width, height } => write!(
+                f,
+                "a {}x{} pattern is too large for a universe",
+                width, height
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PatternParseError {}
+
+#[cfg(feature = "wasm")]
+impl From<PatternParseError> for JsValue {
+    fn from(error: PatternParseError) -> Self {
+        js_sys::Error::new(&error.to_string()).into()
+    }
+}
@@ -0,0 +1,117 @@
+//! Plaintext (`.cells`) patterns
+//!
+//! Each line is a row of cells, with `.` for a dead cell and `O` for a live
+//! one. Lines starting with `!` are comments, such as `!Name: Glider`.
+
+use super::PatternParseError;
+use crate::Universe;
+
+/// Live cells read from a plaintext pattern along with its dimensions
+pub(crate) struct Plaintext {
+    pub width: u32,
+    pub height: u32,
+    /// (row, col) coordinates of the live cells from the top left corner
+    pub alive_cells: Vec<(i64, i64)>,
+}
+
+/// Parses a plaintext pattern
+///
+/// # Arguments
+///
+/// * `text` - The contents of a `.cells` file
+pub(crate) fn parse(text: &str) -> Result<Plaintext, PatternParseError> {
+    let mut rows: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .map(|(number, line)| (number + 1, line.trim_end()))
+        .filter(|(_, line)| !line.starts_with('!'))
+        .collect();
+    while let Some((_, "")) = rows.last() {
+        rows.pop();
+    }
+
+    let mut width = 0;
+    let mut alive_cells = Vec::new();
+    for (row, (number, line)) in rows.iter().enumerate() {
+        for (col, character) in line.chars().enumerate() {
+            match character {
+                '.' => {}
+                'O' | '*' => alive_cells.push((row as i64, col as i64)),
+                _ => {
+                    return Err(PatternParseError::InvalidCharacter {
+                        line: *number,
+                        character,
+                    })
+                }
+            }
+            width = width.max(col as u32 + 1);
+        }
+    }
+
+    Ok(Plaintext {
+        width,
+        height: rows.len() as u32,
+        alive_cells,
+    })
+}
+
+/// Writes a universe as a plaintext pattern covering the whole grid
+///
+/// # Arguments
+///
+/// * `universe` - The universe to encode
+pub(crate) fn write(universe: &Universe) -> String {
+    let mut text = String::new();
+    for row in 0..universe.height() {
+        for column in 0..universe.width() {
+            text.push(if universe.is_cell_alive(row, column) {
+                'O'
+            } else {
+                '.'
+            });
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_glider() {
+        let glider = parse("!Name: Glider\n!\n.O\n..O\nOOO\n").unwrap();
+        assert_eq!(glider.width, 3);
+        assert_eq!(glider.height, 3);
+        assert_eq!(
+            glider.alive_cells,
+            vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn test_parse_keeps_inner_blank_rows() {
+        let pattern = parse("O\n\n.O\n\n").unwrap();
+        assert_eq!(pattern.height, 3);
+        assert_eq!(pattern.alive_cells, vec![(0, 0), (2, 1)]);
+    }
+
+    #[test]
+    fn test_parse_invalid_character() {
+        assert_eq!(
+            parse("!Name: Bad\n.O\nOxO").err(),
+            Some(PatternParseError::InvalidCharacter {
+                line: 3,
+                character: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn test_write() {
+        let mut universe = Universe::new_empty(3, 2);
+        universe.set_cells(&[(0, 1), (1, 0)]);
+        assert_eq!(write(&universe), ".O.\nO..\n");
+    }
+}
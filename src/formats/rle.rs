@@ -0,0 +1,433 @@
+//! Run Length Encoded (RLE) patterns
+//!
+//! An RLE file starts with optional `#` comment lines (`#N` name, `#C`
+//! comment, `#O` author, ...) followed by a header line such as
+//! `x = 3, y = 3, rule = B3/S23`. The cells follow as runs of `b` (dead)
+//! and `o` (alive) tags, each optionally preceded by a run count, with `$`
+//! ending a row and `!` ending the pattern.
+//!
+//! Patterns of Generations rules use the multi-state tags instead: `.` for
+//! dead cells and `A` to `X` for states 1 to 24, with a prefix from `p` to
+//! `y` adding 24 times its position to the state, so `pA` is state 25.
+
+use std::fmt;
+
+use crate::{Rule, RuleParseError, Universe};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+
+/// The maximum length of a line of cell data written by [`write`]
+const MAX_LINE_LENGTH: usize = 70;
+
+/// Parses an RLE pattern into a new universe sized by its header
+///
+/// # Arguments
+///
+/// * `rle` - The contents of an RLE file
+pub(crate) fn parse(rle: &str) -> Result<Universe, RleError> {
+    let mut lines = rle
+        .lines()
+        .enumerate()
+        .map(|(number, line)| (number + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    let (_, header) = lines.next().ok_or(RleError::MissingHeader)?;
+    let (width, height, rule) = parse_header(header)?;
+
+    let multistate = rule.states() > 2;
+    let mut universe = Universe::try_with_rule(width, height, rule)
+        .map_err(|_| RleError::PatternTooLarge { width, height })?;
+    let (mut row, mut column) = (0u64, 0u64);
+    let mut run_count: Option<u64> = None;
+    let mut prefix: Option<u32> = None;
+
+    'lines: for (number, line) in lines {
+        for character in line.chars() {
+            let invalid = RleError::InvalidCharacter {
+                line: number,
+                character,
+            };
+
+            if let Some(digit) = character.to_digit(10) {
+                let count = run_count
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|count| count.checked_add(digit as u64))
+                    .ok_or(RleError::RunCountTooLarge { line: number })?;
+                run_count = Some(count);
+                continue;
+            }
+            if multistate && ('p'..='y').contains(&character) && prefix.is_none() {
+                prefix = Some(character as u32 - 'p' as u32 + 1);
+                continue;
+            }
+
+            let count = run_count.take().unwrap_or(1);
+            let state = match (prefix.take(), character) {
+                (None, 'b' | '.') => 0,
+                (None, '$') => {
+                    row = row.saturating_add(count);
+                    column = 0;
+                    continue;
+                }
+                (None, '!') => break 'lines,
+                (None, c) if c.is_whitespace() => continue,
+                (prefix, c @ 'A'..='X') if multistate => {
+                    prefix.unwrap_or(0) * 24 + (c as u32 - 'A' as u32 + 1)
+                }
+                (None, c) if c.is_ascii_alphabetic() => 1,
+                _ => return Err(invalid),
+            };
+
+            if state >= rule.states() as u32 {
+                return Err(invalid);
+            }
+            if state != 0 {
+                if row >= height as u64 || column.saturating_add(count) > width as u64 {
+                    return Err(RleError::CellOutOfBounds { row, column });
+                }
+                for offset in 0..count {
+                    universe.set_cell_state(row as u32, (column + offset) as u32, state as u8);
+                }
+            }
+            column = column.saturating_add(count);
+        }
+    }
+
+    Ok(universe)
+}
+
+/// Parses the `x = .., y = .., rule = ..` header line
+fn parse_header(header: &str) -> Result<(u32, u32, Rule), RleError> {
+    let invalid = || RleError::InvalidHeader(header.to_string());
+
+    // The rule comes last and may contain commas itself, as in Larger than
+    // Life rules or Golly's topology suffix
+    let (fields, rulestring) = match header.split_once("rule") {
+        Some((fields, rest)) => {
+            let rulestring = rest.trim_start().strip_prefix('=').ok_or_else(invalid)?;
+            (fields, Some(rulestring))
+        }
+        None => (header, None),
+    };
+
+    let (mut width, mut height, mut rule) = (None, None, Rule::conway());
+    for field in fields.split(',').filter(|field| !field.trim().is_empty()) {
+        let (key, value) = field.split_once('=').ok_or_else(invalid)?;
+        match key.trim() {
+            "x" => width = Some(value.trim().parse().map_err(|_| invalid())?),
+            "y" => height = Some(value.trim().parse().map_err(|_| invalid())?),
+            _ => {}
+        }
+    }
+    // Golly appends the topology after a colon, e.g. `B3/S23:T64,64`
+    if let Some(rulestring) = rulestring {
+        rule = Rule::parse(rulestring.split(':').next().unwrap_or(""))?;
+    }
+
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((width, height, rule)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Writes a universe as an RLE pattern covering the whole grid
+///
+/// # Arguments
+///
+/// * `universe` - The universe to encode
+pub(crate) fn write(universe: &Universe) -> String {
+    let mut rle = format!(
+        "x = {}, y = {}, rule = {}\n",
+        universe.width(),
+        universe.height(),
+        universe.rule()
+    );
+
+    let multistate = universe.rule().states() > 2;
+    let mut writer = RunWriter::default();
+    let mut current_row = 0;
+    for row in 0..universe.height() {
+        let mut runs: Vec<(u8, u32)> = Vec::new();
+        for column in 0..universe.width() {
+            let state = universe.cell_state(row, column);
+            match runs.last_mut() {
+                Some((last_state, count)) if *last_state == state => *count += 1,
+                _ => runs.push((state, 1)),
+            }
+        }
+
+        // Trailing dead cells are implied by the end of the row
+        if let Some((0, _)) = runs.last() {
+            runs.pop();
+        }
+        if runs.is_empty() {
+            continue;
+        }
+
+        // Empty rows in between are skipped with a single counted `$`
+        if row > current_row {
+            writer.push("$", row - current_row);
+            current_row = row;
+        }
+        for (state, count) in runs {
+            writer.push(&state_tag(state, multistate), count);
+        }
+    }
+    writer.push("!", 1);
+
+    rle.push_str(&writer.finish());
+    rle
+}
+
+/// Returns the tag of a cell state, using the multi-state tags for rules
+/// with more than two states
+fn state_tag(state: u8, multistate: bool) -> String {
+    match (state, multistate) {
+        (0, false) => "b".to_string(),
+        (_, false) => "o".to_string(),
+        (0, true) => ".".to_string(),
+        (state, true) => {
+            let (prefix, letter) = ((state - 1) / 24, (state - 1) % 24);
+            let letter = (b'A' + letter) as char;
+            if prefix == 0 {
+                letter.to_string()
+            } else {
+                format!("{}{}", (b'p' + prefix - 1) as char, letter)
+            }
+        }
+    }
+}
+
+/// Accumulates run tokens, wrapping lines at [`MAX_LINE_LENGTH`]
+#[derive(Default)]
+struct RunWriter {
+    output: String,
+    line_length: usize,
+}
+
+impl RunWriter {
+    fn push(&mut self, tag: &str, count: u32) {
+        let token = if count == 1 {
+            tag.to_string()
+        } else {
+            format!("{}{}", count, tag)
+        };
+
+        if self.line_length + token.len() > MAX_LINE_LENGTH {
+            self.output.push('\n');
+            self.line_length = 0;
+        }
+        self.line_length += token.len();
+        self.output.push_str(&token);
+    }
+
+    fn finish(mut self) -> String {
+        self.output.push('\n');
+        self.output
+    }
+}
+
+/// An error returned when an RLE pattern cannot be parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RleError {
+    /// The pattern has no `x = .., y = ..` header line
+    MissingHeader,
+    /// The header line is malformed or lacks the `x` or `y` dimension
+    InvalidHeader(String),
+    /// The rule in the header could not be parsed
+    InvalidRule(RuleParseError),
+    /// The cell data contains a character that is not a run count or tag
+    InvalidCharacter { line: usize, character: char },
+    /// A run count does not fit in 64 bits
+    RunCountTooLarge { line: usize },
+    /// A live cell lies outside of the dimensions given in the header
+    CellOutOfBounds { row: u64, column: u64 },
+    /// The dimensions given in the header are too large for a universe
+    PatternTooLarge { width: u32, height: u32 },
+}
+
+impl From<RuleParseError> for RleError {
+    fn from(error: RuleParseError) -> Self {
+        RleError::InvalidRule(error)
+    }
+}
+
+impl fmt::Display for RleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RleError::MissingHeader => write!(f, "RLE pattern has no header line"),
+            RleError::InvalidHeader(header) => write!(f, "invalid RLE header '{}'", header),
+            RleError::InvalidRule(error) => write!(f, "invalid rule in RLE header: {}", error),
+            RleError::InvalidCharacter { line, character } => {
+                write!(f, "invalid character '{}' on line {}", character, line)
+            }
+            RleError::RunCountTooLarge { line } => {
+                write!(f, "run count on line {} is too large", line)
+            }
+            RleError::CellOutOfBounds { row, column } => write!(
+                f,
+                "cell at row {}, column {} lies outside of the pattern bounds",
+                row, column
+            ),
+            RleError::PatternTooLarge { width, height } => {
+                write!(f, "a {}x{} pattern is too large", width, height)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RleError {}
+
+#[cfg(feature = "wasm")]
+impl From<RleError> for JsValue {
+    fn from(error: RleError) -> Self {
+        js_sys::Error::new(&error.to_string()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLIDER: &str = "#N Glider
+#O Richard K. Guy
+#C The smallest, most common, and first discovered spaceship.
+x = 3, y = 3, rule = B3/S23
+bob$2bo$3o!
+";
+
+    #[test]
+    fn test_parse_glider() {
+        let universe = parse(GLIDER).unwrap();
+        assert_eq!(universe.width(), 3);
+        assert_eq!(universe.height(), 3);
+        assert_eq!(universe.rule(), Rule::conway());
+
+        let alive = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+        assert_eq!(universe.get_cells().count_ones(..), alive.len());
+        for (row, col) in alive {
+            assert!(universe.is_cell_alive(row, col));
+        }
+    }
+
+    #[test]
+    fn test_parse_multiline_and_blank_rows() {
+        let universe = parse("x = 4, y = 5, rule = B36/S23\n2o$\n3$\nb3o!").unwrap();
+        assert_eq!(universe.rulestring(), "B36/S23");
+        assert!(universe.is_cell_alive(0, 0));
+        assert!(universe.is_cell_alive(0, 1));
+        assert!(universe.is_cell_alive(4, 1));
+        assert!(universe.is_cell_alive(4, 3));
+        assert_eq!(universe.get_cells().count_ones(..), 5);
+    }
+
+    #[test]
+    fn test_parse_rule_with_commas() {
+        let universe = parse("x = 2, y = 1, rule = R2,C0,M1,S2..4,B3..3,NN\n2o!").unwrap();
+        assert_eq!(universe.rulestring(), "R2,C0,M1,S2..4,B3..3,NN");
+
+        let universe = parse("x = 2, y = 1, rule = B3/S23:T64,64\n2o!").unwrap();
+        assert_eq!(universe.rule(), Rule::conway());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |rle| parse(rle).err().unwrap();
+
+        assert_eq!(error("#C only a comment"), RleError::MissingHeader);
+        assert!(matches!(error("x = 3\nooo!"), RleError::InvalidHeader(_)));
+        assert!(matches!(
+            error("x = 3, y = 1, rule = B9/S23\nooo!"),
+            RleError::InvalidRule(_)
+        ));
+        assert_eq!(
+            error("x = 3, y = 1\no?o!"),
+            RleError::InvalidCharacter {
+                line: 2,
+                character: '?'
+            }
+        );
+        assert_eq!(
+            error("x = 3, y = 1\n4o!"),
+            RleError::CellOutOfBounds { row: 0, column: 0 }
+        );
+        assert_eq!(
+            error("x = 3, y = 3\n99999999999999999999999o!"),
+            RleError::RunCountTooLarge { line: 2 }
+        );
+        assert_eq!(
+            error("x = 3, y = 3\no18446744073709551615b$18446744073709551615$o!"),
+            RleError::CellOutOfBounds {
+                row: u64::MAX,
+                column: 0
+            }
+        );
+        assert_eq!(
+            error("x = 3, y = 3\no18446744073709551615bo!"),
+            RleError::CellOutOfBounds {
+                row: 0,
+                column: u64::MAX
+            }
+        );
+        assert_eq!(
+            error(
+                "x = 100000, y = 100000
+o!"
+            ),
+            RleError::PatternTooLarge {
+                width: 100000,
+                height: 100000
+            }
+        );
+    }
+
+    #[test]
+    fn test_write_glider() {
+        let universe = parse(GLIDER).unwrap();
+        assert_eq!(
+            write(&universe),
+            "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n"
+        );
+    }
+
+    #[test]
+    fn test_multistate_round_trip() {
+        let universe = parse("x = 4, y = 2, rule = B2/S/C3\n.AB$2B.A!").unwrap();
+        assert_eq!(universe.rulestring(), "B2/S/C3");
+        assert_eq!(universe.cell_states(), vec![0, 1, 2, 0, 2, 2, 0, 1]);
+        assert_eq!(
+            write(&universe),
+            "x = 4, y = 2, rule = B2/S/C3\n.AB$2B.A!\n"
+        );
+
+        let mut many_states = Universe::with_rule(3, 1, Rule::generations(&[2], &[], 60));
+        many_states.set_cell_state(0, 0, 25);
+        many_states.set_cell_state(0, 2, 59);
+        let rle = write(&many_states);
+        assert!(rle.ends_with("\npA.qK!\n"));
+        assert_eq!(parse(&rle).unwrap().cell_states(), vec![25, 0, 59]);
+
+        assert!(matches!(
+            parse("x = 1, y = 1, rule = B2/S/C3\nC!").err(),
+            Some(RleError::InvalidCharacter { character: 'C', .. })
+        ));
+    }
+
+    #[test]
+    fn test_write_wraps_long_lines() {
+        let mut universe = Universe::new_empty(200, 5);
+        for column in (0..200).step_by(2) {
+            universe.set_cell(0, column, true);
+        }
+        universe.set_cell(1, 0, true);
+        universe.set_cell(4, 199, true);
+
+        let rle = write(&universe);
+        assert!(rle.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+
+        let round_trip = parse(&rle).unwrap();
+        assert_eq!(round_trip.get_cells(), universe.get_cells());
+    }
+}
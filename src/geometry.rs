@@ -0,0 +1,186 @@
+//! Placement of a universe's cells when it is resized, rotated or flipped
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// The point of a universe that stays in place when it is resized
+///
+/// Cells keep their distance from the anchor, so growing a universe adds
+/// dead cells on the sides away from it and shrinking crops those sides.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Returns how far the cells move when a universe is resized
+    ///
+    /// # Arguments
+    ///
+    /// * `old_size` - The `(width, height)` before resizing
+    /// * `new_size` - The `(width, height)` after resizing
+    ///
+    /// # Returns
+    ///
+    /// The `(rows, columns)` to add to the coordinates of every cell
+    pub(crate) fn offset(self, old_size: (u32, u32), new_size: (u32, u32)) -> (i64, i64) {
+        // 0 keeps the start of an axis in place, 1 its middle and 2 its end
+        let (vertical, horizontal) = match self {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Top => (0, 1),
+            Anchor::TopRight => (0, 2),
+            Anchor::Left => (1, 0),
+            Anchor::Center => (1, 1),
+            Anchor::Right => (1, 2),
+            Anchor::BottomLeft => (2, 0),
+            Anchor::Bottom => (2, 1),
+            Anchor::BottomRight => (2, 2),
+        };
+        let shift = |alignment: i64, old: u32, new: u32| (new as i64 - old as i64) * alignment / 2;
+        (
+            shift(vertical, old_size.1, new_size.1),
+            shift(horizontal, old_size.0, new_size.0),
+        )
+    }
+}
+
+/// One of the eight rotations and reflections of a rectangle of cells
+///
+/// Rotations are clockwise. Quarter turns and both transposes swap the
+/// width and height of the rectangle.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Transform {
+    #[default]
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirrors the columns, so the left edge becomes the right edge
+    FlipHorizontal,
+    /// Mirrors the rows, so the top edge becomes the bottom edge
+    FlipVertical,
+    /// Mirrors across the diagonal from the top left corner
+    Transpose,
+    /// Mirrors across the diagonal from the top right corner
+    AntiTranspose,
+}
+
+impl Transform {
+    /// All eight transforms
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    /// Returns whether the transform swaps the width and height
+    pub fn swaps_dimensions(self) -> bool {
+        matches!(
+            self,
+            Transform::Rotate90
+                | Transform::Rotate270
+                | Transform::Transpose
+                | Transform::AntiTranspose
+        )
+    }
+
+    /// Returns the `(width, height)` of a rectangle after the transform
+    pub(crate) fn size(self, width: u32, height: u32) -> (u32, u32) {
+        if self.swaps_dimensions() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Returns where a cell of a rectangle ends up after the transform
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row of the cell within the rectangle
+    /// * `col` - The column of the cell within the rectangle
+    /// * `width` - The width of the rectangle before the transform
+    /// * `height` - The height of the rectangle before the transform
+    ///
+    /// # Returns
+    ///
+    /// The `(row, col)` of the cell within the transformed rectangle
+    pub(crate) fn apply(self, row: u32, col: u32, width: u32, height: u32) -> (u32, u32) {
+        let (last_row, last_col) = (height - 1, width - 1);
+        match self {
+            Transform::Identity => (row, col),
+            Transform::Rotate90 => (col, last_row - row),
+            Transform::Rotate180 => (last_row - row, last_col - col),
+            Transform::Rotate270 => (last_col - col, row),
+            Transform::FlipHorizontal => (row, last_col - col),
+            Transform::FlipVertical => (last_row - row, col),
+            Transform::Transpose => (col, row),
+            Transform::AntiTranspose => (last_col - col, last_row - row),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(Anchor::TopLeft.offset((4, 4), (10, 2)), (0, 0));
+        assert_eq!(Anchor::Center.offset((4, 4), (10, 2)), (-1, 3));
+        assert_eq!(Anchor::BottomRight.offset((4, 4), (10, 2)), (-2, 6));
+        assert_eq!(Anchor::Top.offset((5, 5), (2, 8)), (0, -1));
+    }
+
+    #[test]
+    fn test_transforms_are_bijections() {
+        let (width, height) = (3, 2);
+        for transform in Transform::ALL {
+            let (new_width, new_height) = transform.size(width, height);
+            let mut seen = vec![false; (width * height) as usize];
+            for row in 0..height {
+                for col in 0..width {
+                    let (new_row, new_col) = transform.apply(row, col, width, height);
+                    assert!(new_row < new_height && new_col < new_width);
+                    seen[(new_row * new_width + new_col) as usize] = true;
+                }
+            }
+            assert!(seen.iter().all(|&seen| seen), "{:?}", transform);
+        }
+
+        // The top left corner of a 3 by 2 rectangle after each transform
+        let corners: Vec<_> = Transform::ALL
+            .iter()
+            .map(|transform| transform.apply(0, 0, 3, 2))
+            .collect();
+        assert_eq!(
+            corners,
+            [
+                (0, 0),
+                (0, 1),
+                (1, 2),
+                (2, 0),
+                (0, 2),
+                (1, 0),
+                (0, 0),
+                (2, 1)
+            ]
+        );
+    }
+}
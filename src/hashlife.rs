@@ -0,0 +1,1040 @@
+//! HashLife simulation of huge and long-running patterns
+//!
+//! HashLife stores an unbounded plane as a quadtree in which identical
+//! subtrees are shared, and memoizes the future of every node. Patterns
+//! with regular structure can then be advanced by billions of generations
+//! at once, at the cost of more memory than a dense [`Universe`].
+
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+
+use crate::rule::NEIGHBOR_OFFSETS;
+use crate::{formats, LifeError, Rule, Simulation, Universe};
+
+/// The index of a node in the node arena
+pub(crate) type NodeId = u32;
+
+/// Marks a node whose successor has not been computed yet
+const NO_RESULT: NodeId = NodeId::MAX;
+
+/// The leaf node for a dead cell
+pub(crate) const DEAD: NodeId = 0;
+
+/// The leaf node for a live cell
+pub(crate) const ALIVE: NodeId = 1;
+
+/// The largest distance of a cell from the origin, so that the root
+/// covering every cell stays small enough to address with `i64`
+const MAX_COORDINATE: i64 = 1 << 60;
+
+/// The number of nodes kept before the node cache is garbage collected
+const DEFAULT_MAX_NODES: usize = 1 << 22;
+
+/// A square quadtree node covering `2^level` by `2^level` cells
+#[derive(Clone, Copy)]
+struct Node {
+    level: u8,
+    /// The north west, north east, south west and south east quadrants
+    children: [NodeId; 4],
+    population: u64,
+    /// The centered node of one level lower, advanced by the largest step
+    /// of `2^(level - 2)` generations
+    result: NodeId,
+}
+
+/// A fast hasher for node children, which are already well distributed ids
+#[derive(Default)]
+struct NodeHasher(u64);
+
+impl Hasher for NodeHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u64(byte as u64);
+        }
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = (self.0.rotate_left(5) ^ value).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+}
+
+type NodeIndex = HashMap<[NodeId; 4], NodeId, BuildHasherDefault<NodeHasher>>;
+
+/// The successors of nodes advanced by less than their largest step, by
+/// node and step exponent
+type SlowResults = HashMap<(NodeId, u32), NodeId, BuildHasherDefault<NodeHasher>>;
+
+/// A Life-like universe on an unbounded plane simulated with HashLife
+///
+/// Cells are addressed by signed `(row, column)` coordinates. The engine
+/// advances the pattern in increments of `2^step_exponent` generations,
+/// and [`Simulation::step`] splits any other number of generations into
+/// such increments.
+pub struct HashLife {
+    nodes: Vec<Node>,
+    index: NodeIndex,
+    slow_results: SlowResults,
+    /// The empty node of each level, created on demand
+    empty: Vec<NodeId>,
+    root: NodeId,
+    /// The (row, column) coordinate of the root's top left cell
+    origin: (i64, i64),
+    rule: Rule,
+    generation: u64,
+    step_exponent: u32,
+    max_nodes: usize,
+}
+
+impl HashLife {
+    /// Creates an empty HashLife universe following Conway's Game of Life
+    pub fn new() -> HashLife {
+        Self::with_rule(Rule::conway())
+    }
+
+    /// Creates an empty HashLife universe following the given rule
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The rule used to advance the universe
+    ///
+    /// # Panics
+    ///
+    /// HashLife relies on empty space staying empty, so rules in which dead
+    /// cells with no live neighbors are born (`B0`) are not supported. Its
+    /// cells are either dead or alive, so neither are Generations rules, and
+    /// it steps 4 by 4 grids of cells, so neither are Larger than Life rules.
+    pub fn with_rule(rule: Rule) -> HashLife {
+        assert!(!rule.is_birth(0), "HashLife does not support B0 rules");
+        assert!(
+            rule.states() == 2,
+            "HashLife does not support Generations rules"
+        );
+        assert!(
+            rule.neighborhood().range() == 1,
+            "HashLife does not support Larger than Life rules"
+        );
+        Self::with_rule_unchecked(rule)
+    }
+
+    /// Returns whether HashLife can advance a universe following a rule
+    ///
+    /// Rules with births on zero neighbors, Generations rules and Larger
+    /// than Life rules are not supported.
+    pub fn supports(rule: &Rule) -> bool {
+        !rule.is_birth(0) && rule.states() == 2 && rule.neighborhood().range() == 1
+    }
+
+    /// Creates an empty HashLife universe without checking that it can
+    /// advance the rule, to store the live cells of any pattern
+    pub(crate) fn with_rule_unchecked(rule: Rule) -> HashLife {
+        let leaf = |population| Node {
+            level: 0,
+            children: [DEAD; 4],
+            population,
+            result: NO_RESULT,
+        };
+
+        let mut hashlife = HashLife {
+            nodes: vec![leaf(0), leaf(1)],
+            index: NodeIndex::default(),
+            slow_results: SlowResults::default(),
+            empty: vec![DEAD],
+            root: DEAD,
+            origin: (0, 0),
+            rule,
+            generation: 0,
+            step_exponent: 0,
+            max_nodes: DEFAULT_MAX_NODES,
+        };
+        hashlife.root = hashlife.empty_node(3);
+        hashlife
+    }
+
+    /// Creates a HashLife universe from every cell of a universe
+    ///
+    /// The top left cell of the universe becomes the cell at `(0, 0)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `universe` - The universe to import, whose rule is used as well
+    ///
+    /// # Panics
+    ///
+    /// Panics if HashLife cannot advance the universe's rule, see
+    /// [`HashLife::supports`] and [`HashLife::try_from_universe`] for a
+    /// fallible version.
+    pub fn from_universe(universe: &Universe) -> HashLife {
+        Self::from_region(universe, 0, 0, universe.width(), universe.height())
+    }
+
+    /// Creates a HashLife universe from every cell of a universe, if
+    /// HashLife can advance the universe's rule
+    ///
+    /// # Arguments
+    ///
+    /// * `universe` - The universe to import, whose rule is used as well
+    ///
+    /// # Returns
+    ///
+    /// An [`LifeError::UnsupportedRule`] error for rules that
+    /// [`HashLife::supports`] rejects
+    pub fn try_from_universe(universe: &Universe) -> Result<HashLife, LifeError> {
+        Self::try_from_region(universe, 0, 0, universe.width(), universe.height())
+    }
+
+    /// Creates a HashLife universe from a rectangular region of a universe
+    ///
+    /// The top left cell of the region becomes the cell at `(0, 0)`, and
+    /// the parts of the region outside of the universe are dead.
+    ///
+    /// # Arguments
+    ///
+    /// * `universe` - The universe to import, whose rule is used as well
+    /// * `row` - The row of the region's top left cell
+    /// * `column` - The column of the region's top left cell
+    /// * `width` - The width of the region
+    /// * `height` - The height of the region
+    ///
+    /// # Panics
+    ///
+    /// Panics if HashLife cannot advance the universe's rule, see
+    /// [`HashLife::supports`] and [`HashLife::try_from_region`] for a
+    /// fallible version.
+    pub fn from_region(
+        universe: &Universe,
+        row: u32,
+        column: u32,
+        width: u32,
+        height: u32,
+    ) -> HashLife {
+        let mut hashlife = Self::with_rule(universe.rule());
+        hashlife.load_region(universe, row, column, width, height);
+        hashlife
+    }
+
+    /// Creates a HashLife universe from a rectangular region of a universe,
+    /// if HashLife can advance the universe's rule
+    ///
+    /// See [`HashLife::from_region`] for the arguments.
+    ///
+    /// # Returns
+    ///
+    /// An [`LifeError::UnsupportedRule`] error for rules that
+    /// [`HashLife::supports`] rejects
+    pub fn try_from_region(
+        universe: &Universe,
+        row: u32,
+        column: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<HashLife, LifeError> {
+        if !Self::supports(&universe.rule()) {
+            return Err(LifeError::UnsupportedRule(universe.rule()));
+        }
+        Ok(Self::from_region(universe, row, column, width, height))
+    }
+
+    /// Replaces the pattern with a rectangular region of a universe, whose
+    /// top left cell becomes the cell at `(0, 0)`
+    pub(crate) fn load_region(
+        &mut self,
+        universe: &Universe,
+        row: u32,
+        column: u32,
+        width: u32,
+        height: u32,
+    ) {
+        let width = width.min(universe.width().saturating_sub(column));
+        let height = height.min(universe.height().saturating_sub(row));
+        let mut level = 3;
+        while (1u64 << level) < width.max(height) as u64 {
+            level += 1;
+        }
+
+        let alive = |cell_row: i64, cell_col: i64| {
+            cell_row < height as i64
+                && cell_col < width as i64
+                && universe.is_cell_alive(row + cell_row as u32, column + cell_col as u32)
+        };
+        self.root = self.build(level, 0, 0, &alive);
+        self.origin = (0, 0);
+    }
+
+    /// Exports a rectangular region of the plane into a new universe
+    ///
+    /// The universe uses this engine's rule and the default topology.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row of the region's top left cell
+    /// * `column` - The column of the region's top left cell
+    /// * `width` - The width of the region
+    /// * `height` - The height of the region
+    ///
+    /// # Panics
+    ///
+    /// Panics if the universe would have more than 2^30 cells, see
+    /// [`HashLife::try_to_universe`] for a fallible version.
+    pub fn to_universe(&self, row: i64, column: i64, width: u32, height: u32) -> Universe {
+        self.try_to_universe(row, column, width, height)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Copies a rectangular region of the plane into a new universe, if it
+    /// is not too large
+    ///
+    /// See [`HashLife::to_universe`] for the arguments.
+    ///
+    /// # Returns
+    ///
+    /// An error if the universe would have more than 2^30 cells
+    pub fn try_to_universe(
+        &self,
+        row: i64,
+        column: i64,
+        width: u32,
+        height: u32,
+    ) -> Result<Universe, LifeError> {
+        let mut universe = Universe::try_with_rule(width, height, self.rule)?;
+        self.for_each_alive(|cell_row, cell_col| {
+            let (region_row, region_col) = (cell_row - row, cell_col - column);
+            if (0..height as i64).contains(&region_row) && (0..width as i64).contains(&region_col) {
+                universe.set_cell(region_row as u32, region_col as u32, true);
+            }
+        });
+        Ok(universe)
+    }
+
+    /// Creates a HashLife universe from a pattern in Golly's macrocell
+    /// (`.mc`) format
+    ///
+    /// The root node of the pattern is centered on the cell at `(0, 0)`,
+    /// and the pattern's rule and generation are used as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of a macrocell file
+    ///
+    /// # Returns
+    ///
+    /// An error if the pattern cannot be parsed or its rule cannot be
+    /// advanced by HashLife
+    pub fn from_macrocell(text: &str) -> Result<HashLife, LifeError> {
+        let hashlife = formats::macrocell::parse(text)?;
+        if !Self::supports(&hashlife.rule) {
+            return Err(LifeError::UnsupportedRule(hashlife.rule));
+        }
+        Ok(hashlife)
+    }
+
+    /// Encodes the pattern in Golly's macrocell (`.mc`) format
+    ///
+    /// Shared subtrees are written once, so the size of the file grows
+    /// with the number of distinct nodes rather than the number of cells.
+    pub fn to_macrocell(&self) -> String {
+        formats::macrocell::write(self)
+    }
+
+    /// Returns the rule used to advance the universe
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Returns whether the cell at the given coordinates is alive
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate
+    /// * `column` - The column coordinate
+    pub fn is_cell_alive(&self, row: i64, column: i64) -> bool {
+        let mut node = self.nodes[self.root as usize];
+        let (mut top, mut left) = self.origin;
+        if !self.contains(node.level, top, left, row, column) {
+            return false;
+        }
+
+        while node.level > 0 {
+            if node.population == 0 {
+                return false;
+            }
+            let half = 1i64 << (node.level - 1);
+            let south = row >= top + half;
+            let east = column >= left + half;
+            top += if south { half } else { 0 };
+            left += if east { half } else { 0 };
+            node = self.nodes[node.children[south as usize * 2 + east as usize] as usize];
+        }
+        node.population == 1
+    }
+
+    /// Sets the cell at the given coordinates to be alive or dead
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate, from -2^60 to 2^60
+    /// * `column` - The column coordinate, from -2^60 to 2^60
+    /// * `alive` - Whether the cell should be alive or dead
+    ///
+    /// # Panics
+    ///
+    /// Panics if either coordinate is out of range, see
+    /// [`HashLife::try_set_cell`] for a fallible version.
+    pub fn set_cell(&mut self, row: i64, column: i64, alive: bool) {
+        self.try_set_cell(row, column, alive)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Sets the cell at the given coordinates to be alive or dead, if they
+    /// are in range
+    ///
+    /// See [`HashLife::set_cell`] for the arguments.
+    ///
+    /// # Returns
+    ///
+    /// An error if either coordinate is further than 2^60 from the origin
+    pub fn try_set_cell(&mut self, row: i64, column: i64, alive: bool) -> Result<(), LifeError> {
+        let range = -MAX_COORDINATE..=MAX_COORDINATE;
+        if !range.contains(&row) || !range.contains(&column) {
+            return Err(LifeError::CoordinateOutOfRange { row, column });
+        }
+
+        loop {
+            let level = self.nodes[self.root as usize].level;
+            let (top, left) = self.origin;
+            if self.contains(level, top, left, row, column) {
+                break;
+            }
+            self.expand();
+        }
+
+        let (top, left) = self.origin;
+        self.root = self.set_in(self.root, top, left, row, column, alive);
+        Ok(())
+    }
+
+    /// Returns the smallest rectangle containing every live cell
+    ///
+    /// # Returns
+    ///
+    /// The `(top, left, bottom, right)` coordinates of the rectangle, with
+    /// inclusive bounds, or `None` if there are no live cells
+    pub fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        let mut bounds: Option<(i64, i64, i64, i64)> = None;
+        self.for_each_alive(|row, column| {
+            bounds = Some(match bounds {
+                None => (row, column, row, column),
+                Some((top, left, bottom, right)) => (
+                    top.min(row),
+                    left.min(column),
+                    bottom.max(row),
+                    right.max(column),
+                ),
+            });
+        });
+        bounds
+    }
+
+    /// Returns the base 2 logarithm of the number of generations advanced
+    /// by each call to [`HashLife::advance`]
+    pub fn step_exponent(&self) -> u32 {
+        self.step_exponent
+    }
+
+    /// Sets the number of generations advanced by each call to
+    /// [`HashLife::advance`] to `2^step_exponent`
+    ///
+    /// The memoized results of every step size are kept, so switching back
+    /// and forth between steps stays fast.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_exponent` - The base 2 logarithm of the step, at most 62
+    pub fn set_step_exponent(&mut self, step_exponent: u32) {
+        self.step_exponent = step_exponent.min(62);
+    }
+
+    /// Advances the universe by `2^step_exponent` generations
+    pub fn advance(&mut self) {
+        if self.nodes.len() > self.max_nodes {
+            self.collect_garbage();
+        }
+
+        // The pattern must fit in the center of a node large enough for the
+        // step, with a border wide enough that it cannot grow past it
+        while self.nodes[self.root as usize].level < self.step_exponent as u8 + 2
+            || !self.is_centered(self.root)
+        {
+            self.expand();
+        }
+        self.expand();
+
+        let level = self.nodes[self.root as usize].level;
+        let offset = 1i64 << (level - 2);
+        self.root = self.successor(self.root);
+        self.origin = (self.origin.0 + offset, self.origin.1 + offset);
+        self.generation += 1 << self.step_exponent;
+
+        self.shrink();
+    }
+
+    /// Returns the number of nodes in the node cache
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Sets the number of nodes the node cache may hold before it is
+    /// garbage collected at the start of the next step
+    ///
+    /// # Arguments
+    ///
+    /// * `max_nodes` - The maximum number of cached nodes
+    pub fn set_max_nodes(&mut self, max_nodes: usize) {
+        self.max_nodes = max_nodes;
+    }
+
+    /// Discards every cached node that is not part of the current pattern,
+    /// along with all memoized results
+    pub fn collect_garbage(&mut self) {
+        let old_nodes = std::mem::take(&mut self.nodes);
+        self.nodes = old_nodes[..2].to_vec();
+        self.index.clear();
+        self.slow_results.clear();
+        self.empty = vec![DEAD];
+
+        let mut remap = HashMap::new();
+        self.root = self.copy_node(&old_nodes, self.root, &mut remap);
+    }
+
+    /// Recursively copies a node from an old arena into the current one
+    fn copy_node(
+        &mut self,
+        old_nodes: &[Node],
+        id: NodeId,
+        remap: &mut HashMap<NodeId, NodeId>,
+    ) -> NodeId {
+        if id <= ALIVE {
+            return id;
+        }
+        if let Some(&new_id) = remap.get(&id) {
+            return new_id;
+        }
+
+        let [nw, ne, sw, se] = old_nodes[id as usize]
+            .children
+            .map(|child| self.copy_node(old_nodes, child, remap));
+        let new_id = self.join(nw, ne, sw, se);
+        remap.insert(id, new_id);
+        new_id
+    }
+
+    /// Returns the root node and the (row, column) coordinate of its top
+    /// left cell
+    pub(crate) fn root(&self) -> (NodeId, (i64, i64)) {
+        (self.root, self.origin)
+    }
+
+    /// Replaces the pattern with the cells of a node
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - A node of level 3 or higher
+    /// * `origin` - The (row, column) coordinate of the node's top left cell
+    pub(crate) fn set_root(&mut self, root: NodeId, origin: (i64, i64)) {
+        self.root = root;
+        self.origin = origin;
+    }
+
+    /// Sets the generation counter, e.g. to the generation a pattern was
+    /// saved at
+    pub(crate) fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    /// Returns the level, the north west, north east, south west and south
+    /// east quadrants, and the population of a node
+    pub(crate) fn node(&self, id: NodeId) -> (u8, [NodeId; 4], u64) {
+        let node = &self.nodes[id as usize];
+        (node.level, node.children, node.population)
+    }
+
+    /// Returns the canonical node with the given quadrants
+    pub(crate) fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let children = [nw, ne, sw, se];
+        if let Some(&id) = self.index.get(&children) {
+            return id;
+        }
+
+        let population = children.iter().fold(0u64, |total, &child| {
+            total.saturating_add(self.nodes[child as usize].population)
+        });
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            level: self.nodes[nw as usize].level + 1,
+            children,
+            population,
+            result: NO_RESULT,
+        });
+        self.index.insert(children, id);
+        id
+    }
+
+    /// Returns the node of the given level with no live cells
+    pub(crate) fn empty_node(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let below = *self.empty.last().unwrap();
+            let id = self.join(below, below, below, below);
+            self.empty.push(id);
+        }
+        self.empty[level as usize]
+    }
+
+    /// Builds the node of the given level whose top left cell is at
+    /// `(top, left)` from a function telling which cells are alive
+    pub(crate) fn build(
+        &mut self,
+        level: u8,
+        top: i64,
+        left: i64,
+        alive: &dyn Fn(i64, i64) -> bool,
+    ) -> NodeId {
+        if level == 0 {
+            return if alive(top, left) { ALIVE } else { DEAD };
+        }
+
+        let half = 1i64 << (level - 1);
+        let nw = self.build(level - 1, top, left, alive);
+        let ne = self.build(level - 1, top, left + half, alive);
+        let sw = self.build(level - 1, top + half, left, alive);
+        let se = self.build(level - 1, top + half, left + half, alive);
+        self.join(nw, ne, sw, se)
+    }
+
+    /// Returns whether a node at `(top, left)` contains the given cell
+    fn contains(&self, level: u8, top: i64, left: i64, row: i64, column: i64) -> bool {
+        // Offsets that overflow are further away than any node reaches
+        let inside = |value: i64, start: i64| {
+            value
+                .checked_sub(start)
+                .is_some_and(|offset| (0..1i64 << level).contains(&offset))
+        };
+        inside(row, top) && inside(column, left)
+    }
+
+    /// Returns a copy of a node with a single cell changed
+    fn set_in(
+        &mut self,
+        id: NodeId,
+        top: i64,
+        left: i64,
+        row: i64,
+        column: i64,
+        alive: bool,
+    ) -> NodeId {
+        let node = self.nodes[id as usize];
+        if node.level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+
+        let half = 1i64 << (node.level - 1);
+        let south = row >= top + half;
+        let east = column >= left + half;
+        let quadrant = south as usize * 2 + east as usize;
+        let mut children = node.children;
+        children[quadrant] = self.set_in(
+            children[quadrant],
+            top + if south { half } else { 0 },
+            left + if east { half } else { 0 },
+            row,
+            column,
+            alive,
+        );
+        let [nw, ne, sw, se] = children;
+        self.join(nw, ne, sw, se)
+    }
+
+    /// Calls a function with the coordinates of every live cell
+    fn for_each_alive(&self, visit: impl FnMut(i64, i64)) {
+        self.for_each_alive_in(self.root, self.origin, visit);
+    }
+
+    /// Calls a function with the coordinates of every live cell of a node
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The node to visit
+    /// * `origin` - The (row, column) coordinate of the node's top left cell
+    /// * `visit` - The function called with the (row, column) of each cell
+    pub(crate) fn for_each_alive_in(
+        &self,
+        id: NodeId,
+        origin: (i64, i64),
+        mut visit: impl FnMut(i64, i64),
+    ) {
+        let mut stack = vec![(id, origin.0, origin.1)];
+        while let Some((id, top, left)) = stack.pop() {
+            let node = self.nodes[id as usize];
+            if node.population == 0 {
+                continue;
+            }
+            if node.level == 0 {
+                visit(top, left);
+                continue;
+            }
+
+            let half = 1i64 << (node.level - 1);
+            let [nw, ne, sw, se] = node.children;
+            stack.push((se, top + half, left + half));
+            stack.push((sw, top + half, left));
+            stack.push((ne, top, left + half));
+            stack.push((nw, top, left));
+        }
+    }
+
+    /// Returns the node of one level lower covering the center of a node
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.nodes[id as usize].children;
+        let nw_se = self.nodes[nw as usize].children[3];
+        let ne_sw = self.nodes[ne as usize].children[2];
+        let sw_ne = self.nodes[sw as usize].children[1];
+        let se_nw = self.nodes[se as usize].children[0];
+        self.join(nw_se, ne_sw, sw_ne, se_nw)
+    }
+
+    /// Returns whether every live cell of a node lies in its central half
+    fn is_centered(&mut self, id: NodeId) -> bool {
+        let center = self.center(id);
+        self.nodes[center as usize].population == self.nodes[id as usize].population
+    }
+
+    /// Doubles the size of the root, keeping the pattern in its center
+    fn expand(&mut self) {
+        let root = self.nodes[self.root as usize];
+        let [nw, ne, sw, se] = root.children;
+        let empty = self.empty_node(root.level - 1);
+
+        let nw = self.join(empty, empty, empty, nw);
+        let ne = self.join(empty, empty, ne, empty);
+        let sw = self.join(empty, sw, empty, empty);
+        let se = self.join(se, empty, empty, empty);
+        self.root = self.join(nw, ne, sw, se);
+
+        let offset = 1i64 << (root.level - 1);
+        self.origin = (self.origin.0 - offset, self.origin.1 - offset);
+    }
+
+    /// Halves the size of the root while the pattern fits in its center
+    fn shrink(&mut self) {
+        while self.nodes[self.root as usize].level > 3 && self.is_centered(self.root) {
+            let offset = 1i64 << (self.nodes[self.root as usize].level - 2);
+            self.root = self.center(self.root);
+            self.origin = (self.origin.0 + offset, self.origin.1 + offset);
+        }
+    }
+
+    /// Returns the center of a node of level 2 or higher, advanced by
+    /// `2^min(level - 2, step_exponent)` generations
+    fn successor(&mut self, id: NodeId) -> NodeId {
+        let node = self.nodes[id as usize];
+        // Nodes small enough to advance by their largest step keep that
+        // result in the node, and larger ones by the step exponent
+        let full_speed = self.step_exponent + 2 >= node.level as u32;
+        let memoized = if full_speed {
+            Some(node.result).filter(|&result| result != NO_RESULT)
+        } else {
+            self.slow_results.get(&(id, self.step_exponent)).copied()
+        };
+        if let Some(result) = memoized {
+            return result;
+        }
+
+        let result = if node.population == 0 {
+            self.empty_node(node.level - 1)
+        } else if node.level == 2 {
+            self.base_successor(id)
+        } else {
+            self.recursive_successor(node)
+        };
+
+        if full_speed {
+            self.nodes[id as usize].result = result;
+        } else {
+            self.slow_results.insert((id, self.step_exponent), result);
+        }
+        result
+    }
+
+    /// Computes the successor of a level 2 node by applying the rule to
+    /// the central 2 by 2 cells of its 4 by 4 grid
+    fn base_successor(&mut self, id: NodeId) -> NodeId {
+        let mut grid = [[false; 4]; 4];
+        for (quadrant, &child) in self.nodes[id as usize].children.iter().enumerate() {
+            for (cell, &leaf) in self.nodes[child as usize].children.iter().enumerate() {
+                let row = (quadrant / 2) * 2 + cell / 2;
+                let col = (quadrant % 2) * 2 + cell % 2;
+                grid[row][col] = leaf == ALIVE;
+            }
+        }
+
+        let mut next = [DEAD; 4];
+        for (cell, next_cell) in next.iter_mut().enumerate() {
+            let (row, col) = (1 + cell / 2, 1 + cell % 2);
+            let neighbors = NEIGHBOR_OFFSETS.into_iter().enumerate().fold(
+                0,
+                |neighbors, (bit, (delta_row, delta_col))| {
+                    let neighbor_row = (row as i64 + delta_row) as usize;
+                    let neighbor_col = (col as i64 + delta_col) as usize;
+                    neighbors | (grid[neighbor_row][neighbor_col] as u8) << bit
+                },
+            );
+            if self
+                .rule
+                .next_state_for_neighbors(grid[row][col], neighbors)
+            {
+                *next_cell = ALIVE;
+            }
+        }
+
+        let [nw, ne, sw, se] = next;
+        self.join(nw, ne, sw, se)
+    }
+
+    /// Computes the successor of a node of level 3 or higher from the
+    /// successors of its nine overlapping sub-nodes
+    fn recursive_successor(&mut self, node: Node) -> NodeId {
+        let [nw, ne, sw, se] = node.children;
+        let [_, nw_ne, nw_sw, nw_se] = self.nodes[nw as usize].children;
+        let [ne_nw, _, ne_sw, ne_se] = self.nodes[ne as usize].children;
+        let [sw_nw, sw_ne, _, sw_se] = self.nodes[sw as usize].children;
+        let [se_nw, se_ne, se_sw, _] = self.nodes[se as usize].children;
+
+        let n00 = nw;
+        let n01 = self.join(nw_ne, ne_nw, nw_se, ne_sw);
+        let n02 = ne;
+        let n10 = self.join(nw_sw, nw_se, sw_nw, sw_ne);
+        let n11 = self.join(nw_se, ne_sw, sw_ne, se_nw);
+        let n12 = self.join(ne_sw, ne_se, se_nw, se_ne);
+        let n20 = sw;
+        let n21 = self.join(sw_ne, se_nw, sw_se, se_sw);
+        let n22 = se;
+
+        // At full speed both halves of the step are taken recursively,
+        // otherwise the first half is skipped by only taking the centers
+        let full_speed = self.step_exponent + 2 >= node.level as u32;
+        let advance = |hashlife: &mut HashLife, id| {
+            if full_speed {
+                hashlife.successor(id)
+            } else {
+                hashlife.center(id)
+            }
+        };
+        let r00 = advance(self, n00);
+        let r01 = advance(self, n01);
+        let r02 = advance(self, n02);
+        let r10 = advance(self, n10);
+        let r11 = advance(self, n11);
+        let r12 = advance(self, n12);
+        let r20 = advance(self, n20);
+        let r21 = advance(self, n21);
+        let r22 = advance(self, n22);
+
+        let nw = self.join(r00, r01, r10, r11);
+        let ne = self.join(r01, r02, r11, r12);
+        let sw = self.join(r10, r11, r20, r21);
+        let se = self.join(r11, r12, r21, r22);
+        let nw = self.successor(nw);
+        let ne = self.successor(ne);
+        let sw = self.successor(sw);
+        let se = self.successor(se);
+        self.join(nw, ne, sw, se)
+    }
+}
+
+impl Default for HashLife {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulation for HashLife {
+    /// Advances the universe by splitting the number of generations into
+    /// increments of powers of two
+    fn step(&mut self, generations: u64) {
+        let previous_exponent = self.step_exponent;
+        for exponent in 0..64 {
+            if generations & (1 << exponent) != 0 {
+                // The largest step is 2^62, so 2^63 takes two of them
+                let (exponent, repeats) = if exponent == 63 {
+                    (62, 2)
+                } else {
+                    (exponent, 1)
+                };
+                self.set_step_exponent(exponent);
+                for _ in 0..repeats {
+                    self.advance();
+                }
+            }
+        }
+        self.set_step_exponent(previous_exponent);
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn population(&self) -> u64 {
+        self.nodes[self.root as usize].population
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// Returns a glider heading south east in a 16 by 16 universe
+    fn glider_universe() -> Universe {
+        Universe::new_with_pattern(16, 16, vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)])
+    }
+
+    #[test]
+    fn test_set_and_read_cells() {
+        let mut hashlife = HashLife::new();
+        hashlife.set_cell(-100, 250, true);
+        hashlife.set_cell(3, -7, true);
+
+        assert!(hashlife.is_cell_alive(-100, 250));
+        assert!(hashlife.is_cell_alive(3, -7));
+        assert!(!hashlife.is_cell_alive(3, -6));
+        assert_eq!(hashlife.population(), 2);
+        assert_eq!(hashlife.bounding_box(), Some((-100, -7, 3, 250)));
+
+        hashlife.set_cell(3, -7, false);
+        assert_eq!(hashlife.population(), 1);
+    }
+
+    #[test]
+    fn test_matches_universe_tick() {
+        for rule in ["B3/S23", "B3/S2-i34q", "B2/S34H"] {
+            // A random soup in the middle of a universe large enough that
+            // nothing reaches the edge within the tested generations
+            let mut universe = Universe::with_rule(64, 64, Rule::parse(rule).unwrap());
+            let mut rng = rand::thread_rng();
+            for row in 24..40 {
+                for col in 24..40 {
+                    universe.set_cell(row, col, rng.gen_bool(0.5));
+                }
+            }
+            let mut hashlife = HashLife::from_universe(&universe);
+
+            for generations in [1, 1, 2, 3, 5, 7] {
+                for _ in 0..generations {
+                    universe.tick();
+                }
+                hashlife.step(generations);
+
+                let exported = hashlife.to_universe(0, 0, 64, 64);
+                assert_eq!(exported.get_cells(), universe.get_cells());
+            }
+            assert_eq!(hashlife.generation(), 19);
+        }
+    }
+
+    #[test]
+    fn test_glider_travels_far() {
+        let mut hashlife = HashLife::from_universe(&glider_universe());
+
+        hashlife.step(1 << 20);
+
+        // A glider moves one cell diagonally every four generations
+        let offset = 1 << 18;
+        assert_eq!(hashlife.generation(), 1 << 20);
+        assert_eq!(hashlife.population(), 5);
+        assert_eq!(
+            hashlife.bounding_box(),
+            Some((offset, offset, offset + 2, offset + 2))
+        );
+
+        let exported = hashlife.to_universe(offset, offset, 16, 16);
+        assert_eq!(exported.get_cells(), glider_universe().get_cells());
+    }
+
+    #[test]
+    fn test_step_exponent_and_garbage_collection() {
+        let mut hashlife = HashLife::from_universe(&glider_universe());
+        hashlife.set_step_exponent(4);
+        hashlife.advance();
+        hashlife.advance();
+        assert_eq!(hashlife.generation(), 32);
+        assert_eq!(hashlife.bounding_box(), Some((8, 8, 10, 10)));
+
+        let before = hashlife.node_count();
+        hashlife.collect_garbage();
+        assert!(hashlife.node_count() < before);
+        assert_eq!(hashlife.population(), 5);
+        assert_eq!(hashlife.bounding_box(), Some((8, 8, 10, 10)));
+
+        hashlife.set_max_nodes(0);
+        hashlife.advance();
+        assert_eq!(hashlife.bounding_box(), Some((12, 12, 14, 14)));
+    }
+
+    #[test]
+    fn test_steps_of_any_size_keep_memoized_results() {
+        let memoized = |hashlife: &HashLife| {
+            let results = hashlife
+                .nodes
+                .iter()
+                .filter(|node| node.result != NO_RESULT);
+            results.count() + hashlife.slow_results.len()
+        };
+
+        let mut hashlife = HashLife::from_universe(&glider_universe());
+        hashlife.step(3);
+        let after_first_step = memoized(&hashlife);
+        assert!(after_first_step > 0);
+        hashlife.step(5);
+        assert!(memoized(&hashlife) >= after_first_step);
+        hashlife.step(1000);
+
+        assert_eq!(hashlife.generation(), 1008);
+        assert_eq!(hashlife.bounding_box(), Some((252, 252, 254, 254)));
+    }
+
+    #[test]
+    fn test_coordinate_range() {
+        let mut hashlife = HashLife::new();
+        hashlife.set_cell(MAX_COORDINATE, -MAX_COORDINATE, true);
+        hashlife.set_cell(-MAX_COORDINATE, MAX_COORDINATE, true);
+        assert!(hashlife.is_cell_alive(MAX_COORDINATE, -MAX_COORDINATE));
+        assert!(!hashlife.is_cell_alive(i64::MAX, i64::MIN));
+        assert_eq!(hashlife.population(), 2);
+
+        assert_eq!(
+            hashlife.try_set_cell(i64::MAX, 0, true),
+            Err(LifeError::CoordinateOutOfRange {
+                row: i64::MAX,
+                column: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_from_universe_rejects_unsupported_rules() {
+        assert!(HashLife::try_from_universe(&glider_universe()).is_ok());
+
+        let rule = Rule::parse("B3/S23/C3").unwrap();
+        let universe = Universe::with_rule(8, 8, rule);
+        assert_eq!(
+            HashLife::try_from_universe(&universe).err(),
+            Some(LifeError::UnsupportedRule(rule))
+        );
+    }
+}
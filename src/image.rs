@@ -0,0 +1,592 @@
+//! Rendering universes as images
+//!
+//! For raster formats, a universe is drawn into an indexed image, with one
+//! palette entry per kind of pixel, which the image formats then encode.
+//! SVG images are written directly, one rectangle per run of cells.
+
+use std::collections::HashSet;
+use std::fmt;
+
+#[cfg(any(feature = "png", feature = "gif"))]
+use crate::LifeError;
+use crate::Universe;
+
+/// An RGB color
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl fmt::Display for Color {
+    /// Formats the color as a CSS hex color, e.g. `#ff8000`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const GRAY: Color = Color::rgb(128, 128, 128);
+    pub const RED: Color = Color::rgb(255, 0, 0);
+
+    /// Creates a color from its red, green and blue components
+    pub const fn rgb(red: u8, green: u8, blue: u8) -> Color {
+        Color { red, green, blue }
+    }
+}
+
+/// How a universe is drawn as an image
+///
+/// Options not given can be taken from the default, e.g.
+/// `ImageOptions { cell_size: 4, ..ImageOptions::default() }`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImageOptions {
+    /// The width and height of each cell in pixels
+    pub cell_size: u32,
+    /// The color of the one pixel wide lines around every cell, or `None`
+    /// to draw the cells without gridlines
+    pub gridlines: Option<Color>,
+    /// The color of live cells
+    pub alive: Color,
+    /// The color of dead cells
+    pub dead: Color,
+    /// The color of dying cells under a Generations rule
+    pub dying: Color,
+    /// The color of the outline of highlighted cells in SVG images
+    pub highlight: Color,
+    /// The `(row, column, width, height)` of the region of the universe to
+    /// draw, or `None` to draw the whole universe. The parts of the region
+    /// outside of the universe are left out.
+    pub region: Option<(u32, u32, u32, u32)>,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        ImageOptions {
+            cell_size: 8,
+            gridlines: None,
+            alive: Color::BLACK,
+            dead: Color::WHITE,
+            dying: Color::GRAY,
+            highlight: Color::RED,
+            region: None,
+        }
+    }
+}
+
+/// How a simulation run is recorded as an animated GIF
+#[cfg(feature = "gif")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GifOptions {
+    /// How each frame is drawn
+    pub image: ImageOptions,
+    /// The number of generations between frames
+    pub stride: u32,
+    /// The time each frame is shown, in hundredths of a second
+    pub delay: u16,
+    /// The number of times the animation plays again after the first time,
+    /// or `None` to loop forever
+    pub repeat: Option<u16>,
+}
+
+#[cfg(feature = "gif")]
+impl Default for GifOptions {
+    fn default() -> Self {
+        GifOptions {
+            image: ImageOptions::default(),
+            stride: 1,
+            delay: 10,
+            repeat: None,
+        }
+    }
+}
+
+/// The palette index of dead cells
+#[cfg(any(feature = "png", feature = "gif"))]
+pub(crate) const DEAD: u8 = 0;
+
+/// The palette index of live cells
+#[cfg(any(feature = "png", feature = "gif"))]
+pub(crate) const ALIVE: u8 = 1;
+
+/// The palette index of dying cells
+#[cfg(any(feature = "png", feature = "gif"))]
+pub(crate) const DYING: u8 = 2;
+
+/// The palette index of gridlines
+#[cfg(any(feature = "png", feature = "gif"))]
+pub(crate) const GRID: u8 = 3;
+
+impl ImageOptions {
+    /// Returns the `(row, column, width, height)` of the cells to draw,
+    /// with the region clamped to the universe
+    fn clamped_region(&self, universe: &Universe) -> (u32, u32, u32, u32) {
+        let (row, column, width, height) =
+            self.region
+                .unwrap_or((0, 0, universe.width(), universe.height()));
+        (
+            row,
+            column,
+            width.min(universe.width().saturating_sub(column)),
+            height.min(universe.height().saturating_sub(row)),
+        )
+    }
+
+    /// Returns the colors of the palette indices of a rendered image
+    #[cfg(any(feature = "png", feature = "gif"))]
+    pub(crate) fn palette(&self) -> [Color; 4] {
+        [
+            self.dead,
+            self.alive,
+            self.dying,
+            self.gridlines.unwrap_or(self.dead),
+        ]
+    }
+}
+
+/// The largest number of pixels a raster image is rendered with, so that
+/// a large cell size or universe cannot exhaust memory
+#[cfg(any(feature = "png", feature = "gif"))]
+pub(crate) const MAX_IMAGE_PIXELS: u64 = 1 << 28;
+
+/// An image with one palette index per pixel, row by row
+#[cfg(any(feature = "png", feature = "gif"))]
+pub(crate) struct IndexedImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Draws the cells of a universe into an indexed image
+///
+/// # Arguments
+///
+/// * `universe` - The universe to draw
+/// * `options` - The cell size, gridlines and region to draw
+///
+/// # Returns
+///
+/// An error if the image would not have any pixels or more than
+/// [`MAX_IMAGE_PIXELS`] pixels
+#[cfg(any(feature = "png", feature = "gif"))]
+pub(crate) fn render(
+    universe: &Universe,
+    options: &ImageOptions,
+) -> Result<IndexedImage, LifeError> {
+    let (row, column, width, height) = options.clamped_region(universe);
+    if width == 0 || height == 0 || options.cell_size == 0 {
+        return Err(LifeError::EmptyImage);
+    }
+
+    // Gridlines take one pixel before every cell and after the last one
+    let line = options.gridlines.is_some() as u64;
+    let pitch = options.cell_size as u64 + line;
+    let (image_width, image_height) = (width as u64 * pitch + line, height as u64 * pitch + line);
+    if image_width.saturating_mul(image_height) > MAX_IMAGE_PIXELS {
+        return Err(LifeError::ImageTooLarge {
+            width: u32::try_from(image_width).unwrap_or(u32::MAX),
+            height: u32::try_from(image_height).unwrap_or(u32::MAX),
+        });
+    }
+    // Both sides fit in a u32, as their product is at most MAX_IMAGE_PIXELS
+    let (line, pitch) = (line as u32, pitch as u32);
+    let (image_width, image_height) = (image_width as u32, image_height as u32);
+
+    let background = if line == 1 { GRID } else { DEAD };
+    let mut pixels = vec![background; image_width as usize * image_height as usize];
+    for cell_row in 0..height {
+        for cell_col in 0..width {
+            let index = match universe.cell_state(row + cell_row, column + cell_col) {
+                0 => DEAD,
+                1 => ALIVE,
+                _ => DYING,
+            };
+            let (top, left) = (cell_row * pitch + line, cell_col * pitch + line);
+            for pixel_row in top..top + options.cell_size {
+                let start = (pixel_row * image_width + left) as usize;
+                pixels[start..start + options.cell_size as usize].fill(index);
+            }
+        }
+    }
+
+    Ok(IndexedImage {
+        width: image_width,
+        height: image_height,
+        pixels,
+    })
+}
+
+/// Encodes an indexed image as a PNG file
+///
+/// # Arguments
+///
+/// * `image` - The image to encode
+/// * `palette` - The color of each palette index
+#[cfg(feature = "png")]
+pub(crate) fn encode_png(image: &IndexedImage, palette: &[Color]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, image.width, image.height);
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_palette(
+        palette
+            .iter()
+            .flat_map(|color| [color.red, color.green, color.blue])
+            .collect::<Vec<u8>>(),
+    );
+
+    // Writing to memory only fails for invalid image headers, which the
+    // renderer does not produce
+    let mut writer = encoder
+        .write_header()
+        .expect("PNG header of a rendered image is valid");
+    writer
+        .write_image_data(&image.pixels)
+        .expect("PNG data of a rendered image is valid");
+    writer.finish().expect("PNG image is complete");
+    bytes
+}
+
+/// Writes the cells of a universe as an SVG image
+///
+/// The image is measured in cells, scaled to `cell_size` pixels per cell,
+/// and each horizontal run of live or dying cells becomes one rectangle.
+///
+/// # Arguments
+///
+/// * `universe` - The universe to draw
+/// * `options` - The cell size, colors, gridlines and region to draw
+/// * `highlighted` - The `(row, column)` of cells to outline
+pub(crate) fn write_svg(
+    universe: &Universe,
+    options: &ImageOptions,
+    highlighted: &[(u32, u32)],
+) -> String {
+    let (top, left, width, height) = options.clamped_region(universe);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n",
+        width as u64 * options.cell_size as u64,
+        height as u64 * options.cell_size as u64,
+        width,
+        height
+    );
+    svg.push_str(&format!(
+        "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+        width, height, options.dead
+    ));
+
+    // Live cells have state 1 and dying cells any higher state
+    for (live, color) in [(true, options.alive), (false, options.dying)] {
+        let mut runs = String::new();
+        for row in 0..height {
+            let in_run = |col: u32| match universe.cell_state(top + row, left + col) {
+                0 => false,
+                1 => live,
+                _ => !live,
+            };
+            let mut col = 0;
+            while col < width {
+                if !in_run(col) {
+                    col += 1;
+                    continue;
+                }
+                let start = col;
+                while col < width && in_run(col) {
+                    col += 1;
+                }
+                runs.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\"/>\n",
+                    start,
+                    row,
+                    col - start
+                ));
+            }
+        }
+        if !runs.is_empty() {
+            svg.push_str(&format!("<g fill=\"{}\">\n{}</g>\n", color, runs));
+        }
+    }
+
+    if let Some(color) = options.gridlines {
+        let mut path = String::new();
+        for col in 0..=width {
+            path.push_str(&format!("M{} 0V{}", col, height));
+        }
+        for row in 0..=height {
+            path.push_str(&format!("M0 {}H{}", row, width));
+        }
+        svg.push_str(&format!(
+            "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1\" \
+             vector-effect=\"non-scaling-stroke\"/>\n",
+            path, color
+        ));
+    }
+
+    let mut highlighted: Vec<_> = highlighted
+        .iter()
+        .filter(|&&(row, col)| {
+            (top..top + height).contains(&row) && (left..left + width).contains(&col)
+        })
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    if !highlighted.is_empty() {
+        highlighted.sort_unstable();
+        svg.push_str(&format!(
+            "<g fill=\"none\" stroke=\"{}\" stroke-width=\"2\">\n",
+            options.highlight
+        ));
+        for (row, col) in highlighted {
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"1\" height=\"1\" \
+                 vector-effect=\"non-scaling-stroke\"/>\n",
+                col - left,
+                row - top
+            ));
+        }
+        svg.push_str("</g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Steps a universe and records every `stride`th generation as a frame of
+/// an animated GIF
+///
+/// # Arguments
+///
+/// * `universe` - The universe to step
+/// * `generations` - The number of generations to step
+/// * `options` - The frame stride, delay, repeat count and how frames are drawn
+///
+/// # Returns
+///
+/// The bytes of the GIF file, or an error if the stride is zero or the frames
+/// would have no pixels or be larger than a GIF allows
+#[cfg(feature = "gif")]
+pub(crate) fn record_gif(
+    universe: &mut Universe,
+    generations: u32,
+    options: &GifOptions,
+) -> Result<Vec<u8>, LifeError> {
+    if options.stride == 0 {
+        return Err(LifeError::ZeroStride);
+    }
+
+    let first = render(universe, &options.image)?;
+    let (width, height) = match (u16::try_from(first.width), u16::try_from(first.height)) {
+        (Ok(width), Ok(height)) => (width, height),
+        _ => {
+            return Err(LifeError::ImageTooLarge {
+                width: first.width,
+                height: first.height,
+            })
+        }
+    };
+
+    let palette: Vec<u8> = options
+        .image
+        .palette()
+        .iter()
+        .flat_map(|color| [color.red, color.green, color.blue])
+        .collect();
+    let repeat = match options.repeat {
+        Some(count) => gif::Repeat::Finite(count),
+        None => gif::Repeat::Infinite,
+    };
+
+    // Writing to memory only fails for frames that do not match the screen
+    // size, which every rendered frame does
+    let mut bytes = Vec::new();
+    let mut encoder = gif::Encoder::new(&mut bytes, width, height, &palette)
+        .expect("GIF header of a rendered image is valid");
+    encoder
+        .set_repeat(repeat)
+        .expect("GIF repeat count is valid");
+    let mut write_frame = |image: IndexedImage| {
+        let frame = gif::Frame {
+            delay: options.delay,
+            width,
+            height,
+            buffer: image.pixels.into(),
+            ..gif::Frame::default()
+        };
+        encoder
+            .write_frame(&frame)
+            .expect("GIF frame of a rendered image is valid");
+    };
+
+    write_frame(first);
+    for generation in 1..=generations {
+        universe.tick();
+        if generation % options.stride == 0 {
+            write_frame(render(universe, &options.image)?);
+        }
+    }
+    drop(encoder);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_merges_runs() {
+        let mut universe = Universe::new_empty(8, 4);
+        universe.set_cells(&[(1, 1), (1, 2), (1, 3), (1, 5), (3, 0)]);
+
+        let options = ImageOptions {
+            cell_size: 10,
+            gridlines: Some(Color::GRAY),
+            region: Some((1, 1, 100, 100)),
+            ..ImageOptions::default()
+        };
+        let svg = universe.to_svg(&options, &[(1, 5), (0, 0)]);
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("width=\"70\" height=\"30\" viewBox=\"0 0 7 3\""));
+        assert!(svg.contains("<g fill=\"#000000\">\n"));
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"3\" height=\"1\"/>"));
+        assert!(svg.contains("<rect x=\"4\" y=\"0\" width=\"1\" height=\"1\"/>"));
+        // Cells outside of the region are left out
+        assert_eq!(svg.matches("height=\"1\"/>").count(), 2);
+        assert!(svg.contains("M7 0V3"));
+        assert_eq!(svg.matches("vector-effect").count(), 2);
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[cfg(any(feature = "png", feature = "gif"))]
+    #[test]
+    fn test_render_with_gridlines_and_region() {
+        let mut universe = Universe::new_empty(10, 10);
+        universe.set_cells(&[(2, 3), (4, 4)]);
+
+        let options = ImageOptions {
+            cell_size: 2,
+            gridlines: Some(Color::GRAY),
+            region: Some((2, 3, 100, 2)),
+            ..ImageOptions::default()
+        };
+        let image = render(&universe, &options).unwrap();
+
+        // 7 columns and 2 rows of cells, each 2 pixels plus a gridline
+        assert_eq!((image.width, image.height), (22, 7));
+        let pixel = |x: u32, y: u32| image.pixels[(y * image.width + x) as usize];
+        assert_eq!(pixel(0, 0), GRID);
+        assert_eq!(pixel(1, 1), ALIVE);
+        assert_eq!(pixel(2, 2), ALIVE);
+        assert_eq!(pixel(3, 1), GRID);
+        assert_eq!(pixel(4, 1), DEAD);
+    }
+
+    #[cfg(any(feature = "png", feature = "gif"))]
+    #[test]
+    fn test_render_empty_region() {
+        let universe = Universe::new_empty(10, 10);
+        let options = ImageOptions {
+            region: Some((10, 0, 5, 5)),
+            ..ImageOptions::default()
+        };
+        assert_eq!(
+            render(&universe, &options).err(),
+            Some(LifeError::EmptyImage)
+        );
+    }
+
+    #[cfg(any(feature = "png", feature = "gif"))]
+    #[test]
+    fn test_render_too_large() {
+        let universe = Universe::new_empty(5000, 2);
+        let options = ImageOptions {
+            cell_size: 1_000_000,
+            ..ImageOptions::default()
+        };
+        assert_eq!(
+            render(&universe, &options).err(),
+            Some(LifeError::ImageTooLarge {
+                width: u32::MAX,
+                height: 2_000_000,
+            })
+        );
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_png_round_trip() {
+        let mut universe = Universe::new_empty(4, 3);
+        universe.set_cell(1, 2, true);
+
+        let options = ImageOptions {
+            cell_size: 3,
+            alive: Color::rgb(255, 0, 0),
+            ..ImageOptions::default()
+        };
+        let bytes = universe.to_png(&options).unwrap();
+
+        let mut decoder = png::Decoder::new(bytes.as_slice());
+        decoder.set_transformations(png::Transformations::EXPAND);
+        let mut reader = decoder.read_info().unwrap();
+        let mut rgb = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut rgb).unwrap();
+        assert_eq!((info.width, info.height), (12, 9));
+
+        let color = |x: usize, y: usize| {
+            let start = (y * 12 + x) * 3;
+            Color::rgb(rgb[start], rgb[start + 1], rgb[start + 2])
+        };
+        assert_eq!(color(7, 4), Color::rgb(255, 0, 0));
+        assert_eq!(color(0, 0), Color::WHITE);
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn test_gif_frames() {
+        use crate::{Simulation, Transform};
+
+        let mut universe = Universe::new_empty(5, 5);
+        universe.draw_blinker(2, 2, Transform::Identity, 0);
+
+        let options = GifOptions {
+            image: ImageOptions {
+                cell_size: 2,
+                ..ImageOptions::default()
+            },
+            stride: 2,
+            delay: 25,
+            repeat: Some(3),
+        };
+        let bytes = universe.record_gif(5, &options).unwrap();
+        assert_eq!(universe.generation(), 5);
+
+        let mut decoder = gif::DecodeOptions::new();
+        decoder.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = decoder.read_info(bytes.as_slice()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (10, 10));
+
+        // Generations 0, 2 and 4 all show the horizontal phase
+        let mut frames = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 25);
+            assert_eq!(frame.buffer[4 * 10 + 2], ALIVE);
+            assert_eq!(frame.buffer[2 * 10 + 4], DEAD);
+            frames += 1;
+        }
+        assert_eq!(frames, 3);
+
+        let options = GifOptions {
+            stride: 0,
+            ..options
+        };
+        assert_eq!(
+            universe.record_gif(5, &options).err(),
+            Some(LifeError::ZeroStride)
+        );
+        assert_eq!(universe.generation(), 5);
+    }
+}
@@ -0,0 +1,507 @@
+//! Bit-parallel stepping kernel
+//!
+//! Rather than counting the neighbors of one cell at a time, the kernel
+//! works on 64 cells of a row at once. Each row is copied into words with
+//! a one cell halo on every side, taken from the universe's topology, so
+//! the eight neighbors of all 64 cells are plain shifts of three padded
+//! rows. Their counts are summed with bitwise full adders into four bit
+//! planes, which the rule is then evaluated against.
+//!
+//! The von Neumann and hexagonal neighborhoods leave some of the eight
+//! neighbors out of the sum. Non-totalistic rules depend on the arrangement
+//! of the neighbors rather than their count, so each cell of the 64 looks
+//! its 3 by 3 neighborhood up in a 512 entry table instead.
+//!
+//! Larger than Life rules count neighbors beyond the 3 by 3 neighborhood
+//! and are stepped by [`crate::extended`] instead.
+
+use fixedbitset::{Block, FixedBitSet};
+
+use crate::{Rule, Universe};
+
+/// The number of bits in a block of a [`FixedBitSet`]
+const BLOCK_BITS: usize = Block::BITS as usize;
+
+/// The number of rows stepped together by one thread
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+const BAND_ROWS: usize = 32;
+
+/// Buffers reused between ticks so stepping does not allocate
+#[derive(Clone, Default)]
+pub(crate) struct TickBuffers {
+    /// Rows of the universe with a one cell halo, `padded_stride` words each
+    padded: Vec<u64>,
+    /// The next state of each row, `row_stride` words each
+    rows: Vec<u64>,
+    /// The next state of the universe, swapped with its cells after a tick
+    pub next: FixedBitSet,
+    /// The summed-area table of an extended-range neighborhood
+    pub sums: Vec<u32>,
+}
+
+/// Computes the next generation of a universe into `buffers.next`
+///
+/// # Arguments
+///
+/// * `universe` - The universe to step
+/// * `buffers` - Buffers reused between ticks
+pub(crate) fn step(universe: &Universe, buffers: &mut TickBuffers) {
+    let (width, height) = (universe.width() as usize, universe.height() as usize);
+    let row_stride = width.div_ceil(64);
+    // One extra word lets the shifts read past the last padded word
+    let padded_stride = (width + 2).div_ceil(64) + 1;
+
+    pad_rows(universe, padded_stride, &mut buffers.padded);
+
+    buffers.rows.clear();
+    buffers.rows.resize(row_stride * height, 0);
+    let transition = Transition::new(&universe.rule());
+    step_bands(
+        &buffers.padded,
+        padded_stride,
+        &mut buffers.rows,
+        row_stride,
+        width,
+        &transition,
+    );
+
+    if buffers.next.len() != width * height {
+        buffers.next = FixedBitSet::with_capacity(width * height);
+    }
+    let blocks = buffers.next.as_mut_slice();
+    for (row, words) in buffers.rows.chunks(row_stride.max(1)).enumerate() {
+        for (index, &word) in words.iter().enumerate() {
+            let len = (width - index * 64).min(64);
+            write_bits(blocks, row * width + index * 64, word, len);
+        }
+    }
+}
+
+/// Copies every row of the universe into `padded`, shifted one bit to make
+/// room for the cell left of the row, with halo rows above and below
+fn pad_rows(universe: &Universe, stride: usize, padded: &mut Vec<u64>) {
+    let (width, height) = (universe.width() as usize, universe.height() as usize);
+    padded.clear();
+    padded.resize(stride * (height + 2), 0);
+
+    let blocks = universe.get_cells().as_slice();
+    let interior = &mut padded[stride..(height + 1) * stride];
+    let copy_row = |(row, words): (usize, &mut [u64])| {
+        let mut carry = 0;
+        for (index, word) in words.iter_mut().enumerate() {
+            let start = index * 64;
+            let bits = if start < width {
+                read_bits(blocks, row * width + start, (width - start).min(64))
+            } else {
+                0
+            };
+            *word = (bits << 1) | carry;
+            carry = bits >> 63;
+        }
+    };
+
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        use rayon::prelude::*;
+        interior
+            .par_chunks_mut(stride)
+            .enumerate()
+            .for_each(copy_row);
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    interior.chunks_mut(stride).enumerate().for_each(copy_row);
+
+    // Halo cells take their state from the universe's topology
+    let halo = |row: i64, column: i64| {
+        universe
+            .topology()
+            .wrap(universe.width(), universe.height(), row, column)
+            .is_some_and(|(row, column)| universe.is_cell_alive(row, column))
+    };
+    let set = |padded: &mut Vec<u64>, padded_row: usize, bit: usize| {
+        padded[padded_row * stride + bit / 64] |= 1 << (bit % 64);
+    };
+
+    for row in 0..height {
+        if halo(row as i64, -1) {
+            set(padded, row + 1, 0);
+        }
+        if halo(row as i64, width as i64) {
+            set(padded, row + 1, width + 1);
+        }
+    }
+    for column in -1..=width as i64 {
+        if halo(-1, column) {
+            set(padded, 0, (column + 1) as usize);
+        }
+        if halo(height as i64, column) {
+            set(padded, height + 1, (column + 1) as usize);
+        }
+    }
+}
+
+/// Computes the next state of every row, one band of rows per thread
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+fn step_bands(
+    padded: &[u64],
+    padded_stride: usize,
+    rows: &mut [u64],
+    row_stride: usize,
+    width: usize,
+    transition: &Transition,
+) {
+    use rayon::prelude::*;
+
+    if row_stride == 0 {
+        return;
+    }
+
+    rows.par_chunks_mut(row_stride * BAND_ROWS)
+        .enumerate()
+        .for_each(|(band, rows)| {
+            let first_row = band * BAND_ROWS;
+            let padded = &padded[first_row * padded_stride..];
+            step_rows(padded, padded_stride, rows, row_stride, width, transition);
+        });
+}
+
+/// Computes the next state of every row
+#[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+fn step_bands(
+    padded: &[u64],
+    padded_stride: usize,
+    rows: &mut [u64],
+    row_stride: usize,
+    width: usize,
+    transition: &Transition,
+) {
+    step_rows(padded, padded_stride, rows, row_stride, width, transition);
+}
+
+/// Computes the next state of consecutive rows from their padded rows
+///
+/// `rows` holds `row_stride` words for each output row, and `padded` must
+/// start at the halo row above the first of them.
+fn step_rows(
+    padded: &[u64],
+    padded_stride: usize,
+    rows: &mut [u64],
+    row_stride: usize,
+    width: usize,
+    transition: &Transition,
+) {
+    if row_stride == 0 {
+        return;
+    }
+
+    for (row, words) in rows.chunks_mut(row_stride).enumerate() {
+        let above = &padded[row * padded_stride..(row + 1) * padded_stride];
+        let middle = &padded[(row + 1) * padded_stride..(row + 2) * padded_stride];
+        let below = &padded[(row + 2) * padded_stride..(row + 3) * padded_stride];
+
+        for (index, word) in words.iter_mut().enumerate() {
+            // The padded bit `j` holds column `j - 1`, so shifting by 0, 1
+            // and 2 lines up the left, center and right cells of a column
+            let shifted = |padded: &[u64], shift: u32| {
+                if shift == 0 {
+                    padded[index]
+                } else {
+                    (padded[index] >> shift) | (padded[index + 1] << (64 - shift))
+                }
+            };
+
+            let center = shifted(middle, 1);
+            let neighbors = [
+                shifted(above, 0),
+                shifted(above, 1),
+                shifted(above, 2),
+                shifted(middle, 0),
+                shifted(middle, 2),
+                shifted(below, 0),
+                shifted(below, 1),
+                shifted(below, 2),
+            ];
+
+            let mut next = transition.apply(center, &neighbors);
+            let remaining = width - index * 64;
+            if remaining < 64 {
+                next &= (1 << remaining) - 1;
+            }
+            *word = next;
+        }
+    }
+}
+
+/// Returns the sum and carry of three bit planes
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let partial = a ^ b;
+    (partial ^ c, (a & b) | (partial & c))
+}
+
+/// A rule compiled into the form it is evaluated in
+enum Transition {
+    /// The neighbor counts that keep or make cells alive
+    Totalistic {
+        /// Neighbor counts for which a dead cell is born
+        birth: Vec<u8>,
+        /// Neighbor counts for which a live cell survives
+        survival: Vec<u8>,
+        /// Whether each neighbor is counted, in the order of
+        /// [`Transition::apply`]
+        counted: [bool; 8],
+    },
+    /// Bit `n` is set when a cell whose 3 by 3 neighborhood is `n` is
+    /// alive in the next generation, with the cell itself as bit 8 and its
+    /// neighbors as bits 0 to 7 in the order of
+    /// [`Rule::next_state_for_neighbors`]
+    Lookup([u64; 8]),
+}
+
+impl Transition {
+    fn new(rule: &Rule) -> Transition {
+        if rule.is_totalistic() {
+            // The neighbors NW, N, NE, W, E, SW, S and SE as bits of the rule
+            let mask = rule.neighborhood().mask();
+            let counted = [7, 0, 1, 6, 2, 5, 4, 3].map(|bit| mask & (1 << bit) != 0);
+            return Transition::Totalistic {
+                birth: (0..=8).filter(|&count| rule.is_birth(count)).collect(),
+                survival: (0..=8).filter(|&count| rule.is_survival(count)).collect(),
+                counted,
+            };
+        }
+
+        let mut table = [0; 8];
+        for neighborhood in 0..512usize {
+            let alive = neighborhood & 0x100 != 0;
+            if rule.next_state_for_neighbors(alive, neighborhood as u8) {
+                table[neighborhood / 64] |= 1 << (neighborhood % 64);
+            }
+        }
+        Transition::Lookup(table)
+    }
+
+    /// Returns the next state of 64 cells from their state and neighbors
+    ///
+    /// The neighbors are ordered NW, N, NE, W, E, SW, S and SE.
+    fn apply(&self, center: u64, neighbors: &[u64; 8]) -> u64 {
+        match self {
+            Transition::Totalistic {
+                birth,
+                survival,
+                counted,
+            } => {
+                let mut neighbors = *neighbors;
+                for (plane, &counted) in neighbors.iter_mut().zip(counted) {
+                    if !counted {
+                        *plane = 0;
+                    }
+                }
+                Self::apply_totalistic(birth, survival, center, &neighbors)
+            }
+            Transition::Lookup(table) => Self::apply_lookup(table, center, neighbors),
+        }
+    }
+
+    fn apply_totalistic(birth: &[u8], survival: &[u8], center: u64, neighbors: &[u64; 8]) -> u64 {
+        let [a, b, c, d, e, f, g, h] = *neighbors;
+
+        // Sum the eight neighbor planes into ones, twos, fours and eights
+        let (sum_abc, carry_abc) = full_add(a, b, c);
+        let (sum_def, carry_def) = full_add(d, e, f);
+        let (sum_gh, carry_gh) = (g ^ h, g & h);
+        let (ones, carry_ones) = full_add(sum_abc, sum_def, sum_gh);
+        let (sum_twos, carry_twos) = full_add(carry_abc, carry_def, carry_gh);
+        let (twos, carry_last) = (sum_twos ^ carry_ones, sum_twos & carry_ones);
+        let (fours, eights) = (carry_twos ^ carry_last, carry_twos & carry_last);
+
+        let count_is = |count: u8| {
+            let plane = |bit: u8, plane: u64| if count & bit != 0 { plane } else { !plane };
+            plane(1, ones) & plane(2, twos) & plane(4, fours) & plane(8, eights)
+        };
+
+        let born = birth
+            .iter()
+            .fold(0, |cells, &count| cells | count_is(count));
+        let survive = survival
+            .iter()
+            .fold(0, |cells, &count| cells | count_is(count));
+        (born & !center) | (survive & center)
+    }
+
+    fn apply_lookup(table: &[u64; 8], center: u64, neighbors: &[u64; 8]) -> u64 {
+        let [nw, n, ne, w, e, sw, s, se] = *neighbors;
+        let planes = [n, ne, e, se, s, sw, w, nw, center];
+
+        // Empty neighborhoods stay empty unless the rule has B0
+        if planes.iter().all(|&plane| plane == 0) {
+            return if table[0] & 1 != 0 { u64::MAX } else { 0 };
+        }
+
+        let mut next = 0;
+        for bit in 0..64 {
+            let neighborhood = planes
+                .iter()
+                .enumerate()
+                .fold(0, |index, (position, plane)| {
+                    index | (((plane >> bit) & 1) as usize) << position
+                });
+            next |= ((table[neighborhood / 64] >> (neighborhood % 64)) & 1) << bit;
+        }
+        next
+    }
+}
+
+/// Reads up to 64 bits starting at bit `start` of a bitset's blocks
+fn read_bits(blocks: &[Block], start: usize, len: usize) -> u64 {
+    let mut bits = 0;
+    let mut read = 0;
+    while read < len {
+        let position = start + read;
+        let (block, offset) = (position / BLOCK_BITS, position % BLOCK_BITS);
+        let take = (BLOCK_BITS - offset).min(len - read);
+        let chunk = (blocks[block] >> offset) as u64 & low_mask(take);
+        bits |= chunk << read;
+        read += take;
+    }
+    bits
+}
+
+/// Overwrites `len` bits starting at bit `start` of a bitset's blocks
+fn write_bits(blocks: &mut [Block], start: usize, bits: u64, len: usize) {
+    let mut written = 0;
+    while written < len {
+        let position = start + written;
+        let (block, offset) = (position / BLOCK_BITS, position % BLOCK_BITS);
+        let take = (BLOCK_BITS - offset).min(len - written);
+        let mask = (low_mask(take) as Block) << offset;
+        let chunk = ((bits >> written) as Block) << offset;
+        blocks[block] = (blocks[block] & !mask) | (chunk & mask);
+        written += take;
+    }
+}
+
+/// Returns a mask of the lowest `len` bits
+fn low_mask(len: usize) -> u64 {
+    if len >= 64 {
+        u64::MAX
+    } else {
+        (1 << len) - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule::NEIGHBOR_OFFSETS;
+    use crate::Topology;
+
+    /// Computes the next generation one cell at a time
+    fn reference_step(universe: &Universe) -> FixedBitSet {
+        let (width, height) = (universe.width(), universe.height());
+        let mut next = FixedBitSet::with_capacity((width * height) as usize);
+        for row in 0..height {
+            for col in 0..width {
+                let mut neighbors = 0;
+                for (bit, (delta_row, delta_col)) in NEIGHBOR_OFFSETS.into_iter().enumerate() {
+                    let neighbor = universe.topology().wrap(
+                        width,
+                        height,
+                        row as i64 + delta_row,
+                        col as i64 + delta_col,
+                    );
+                    if let Some((neighbor_row, neighbor_col)) = neighbor {
+                        neighbors |=
+                            (universe.is_cell_alive(neighbor_row, neighbor_col) as u8) << bit;
+                    }
+                }
+                let alive = universe
+                    .rule()
+                    .next_state_for_neighbors(universe.is_cell_alive(row, col), neighbors);
+                next.set((row * width + col) as usize, alive);
+            }
+        }
+        next
+    }
+
+    #[test]
+    fn test_read_write_bits() {
+        let mut bitset = FixedBitSet::with_capacity(200);
+        write_bits(bitset.as_mut_slice(), 60, 0b1011, 4);
+        assert_eq!(bitset.ones().collect::<Vec<_>>(), vec![60, 61, 63]);
+        assert_eq!(read_bits(bitset.as_slice(), 59, 6), 0b10110);
+
+        write_bits(bitset.as_mut_slice(), 61, 0, 2);
+        assert_eq!(bitset.ones().collect::<Vec<_>>(), vec![60, 63]);
+    }
+
+    #[test]
+    fn test_matches_reference_step() {
+        let topologies = [
+            Topology::Torus,
+            Topology::Plane,
+            Topology::CylinderHorizontal,
+            Topology::CylinderVertical,
+            Topology::KleinBottle,
+            Topology::CrossSurface,
+            Topology::ShiftedTorus(7),
+        ];
+        let rules = [
+            "B3/S23",
+            "B36/S23",
+            "B3678/S34678",
+            "B2/S",
+            "B0/S8",
+            "B2-a/S12",
+            "B3/S2-i34q",
+            "B0/S1c2-kn",
+            "B2/S34H",
+            "B13/S012V",
+        ];
+
+        for (width, height) in [(1, 1), (2, 3), (63, 5), (64, 4), (65, 7), (130, 9)] {
+            for topology in topologies {
+                for rule in rules {
+                    let mut universe =
+                        Universe::with_rule(width, height, Rule::parse(rule).unwrap());
+                    universe.set_topology(topology);
+                    universe.randomize();
+
+                    let mut buffers = TickBuffers::default();
+                    step(&universe, &mut buffers);
+                    assert_eq!(
+                        buffers.next,
+                        reference_step(&universe),
+                        "{}x{} {} {}",
+                        width,
+                        height,
+                        topology,
+                        rule
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_parallel_bands_match_single_thread() {
+        let mut universe = Universe::new_random(300, 1000);
+        universe.set_topology(Topology::KleinBottle);
+
+        let mut buffers = TickBuffers::default();
+        step(&universe, &mut buffers);
+
+        let (row_stride, padded_stride) = (300usize.div_ceil(64), 302usize.div_ceil(64) + 1);
+        let mut rows = vec![0; row_stride * 1000];
+        step_rows(
+            &buffers.padded,
+            padded_stride,
+            &mut rows,
+            row_stride,
+            300,
+            &Transition::new(&universe.rule()),
+        );
+        assert_eq!(rows, buffers.rows);
+        assert_eq!(buffers.next, reference_step(&universe));
+    }
+}
@@ -3,22 +3,68 @@ use rand::Rng;
 use std::fmt;
 
 #[cfg(feature = "wasm")]
-use {js_sys, wasm_bindgen::prelude::wasm_bindgen, wasm_bindgen::JsCast};
+use wasm_bindgen::{prelude::wasm_bindgen, JsCast};
 
 #[cfg(feature = "wasm")]
 extern crate web_sys;
 
+mod analysis;
+mod catalog;
+mod error;
+mod extended;
+mod formats;
+mod geometry;
+mod hashlife;
+mod image;
+mod kernel;
+mod neighborhood;
+mod pattern;
+mod patterns;
+mod rule;
+#[cfg(feature = "serde")]
+mod serialization;
+mod simulation;
+mod sparse;
+mod topology;
 mod utils;
 
+pub use analysis::{Cycle, CycleKind};
+pub use catalog::CatalogPattern;
+pub use error::LifeError;
+pub use formats::{rle::RleError, PatternFormat, PatternParseError};
+pub use geometry::{Anchor, Transform};
+pub use hashlife::HashLife;
+#[cfg(feature = "gif")]
+pub use image::GifOptions;
+pub use image::{Color, ImageOptions};
+pub use neighborhood::Neighborhood;
+pub use pattern::{PasteMode, Pattern};
+pub use rule::{Rule, RuleParseError};
+pub use simulation::Simulation;
+pub use sparse::SparseUniverse;
+pub use topology::{Topology, TopologyParseError};
+
 /// The universe for Conway's Game of Life
 ///
 /// This struct represents a 2D grid of cells that can be alive or dead.
-/// It implements the rules of Conway's Game of Life for simulation.
+/// It implements the rules of Conway's Game of Life for simulation by
+/// default, and can be given any other Life-like [`Rule`]. Under a
+/// Generations rule, cells that die pass through refractory states, see
+/// [`Universe::cell_state`].
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone)]
 pub struct Universe {
     width: u32,
     height: u32,
     cells: FixedBitSet,
+    /// The state of each dying cell under a Generations rule, from 2 up to
+    /// the rule's number of states, or 0 for cells that are not dying.
+    /// Empty while no cell is dying.
+    dying: Vec<u8>,
+    rule: Rule,
+    topology: Topology,
+    generation: u64,
+    buffers: kernel::TickBuffers,
 }
 
 /// Private methods for the universe
@@ -33,81 +79,292 @@ impl Universe {
         (row * self.width + column) as usize
     }
 
-    /// Returns the number of live neighbors for a coordinate in the universe
+    /// Returns the number of cells of a universe with the given dimensions
+    ///
+    /// # Returns
+    ///
+    /// An error if the universe would have more than
+    /// [`formats::MAX_UNIVERSE_CELLS`] cells
+    fn cell_count(width: u32, height: u32) -> Result<usize, LifeError> {
+        (width as u64)
+            .checked_mul(height as u64)
+            .filter(|&cells| cells <= formats::MAX_UNIVERSE_CELLS)
+            .map(|cells| cells as usize)
+            .ok_or(LifeError::UniverseTooLarge { width, height })
+    }
+
+    /// Returns the index of a cell after checking that it lies inside the universe
     ///
     /// # Arguments
     ///
-    /// * `row` - A u32 representing the cell's row in the universe
-    /// * `column` - A u32 representing the cell's column in the universe
-    fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
-        let mut count = 0;
-        for delta_row in [self.height - 1, 0, 1].iter().cloned() {
-            for delta_col in [self.width - 1, 0, 1].iter().cloned() {
-                if delta_row == 0 && delta_col == 0 {
-                    continue;
-                }
+    /// * `row` - The row coordinate
+    /// * `column` - The column coordinate
+    fn checked_index(&self, row: u32, column: u32) -> Result<usize, LifeError> {
+        if row < self.height && column < self.width {
+            Ok(self.get_index(row, column))
+        } else {
+            Err(LifeError::CellOutOfBounds {
+                row,
+                column,
+                width: self.width,
+                height: self.height,
+            })
+        }
+    }
 
-                let neighbor_row = (row + delta_row) % self.height;
-                let neighbor_col = (column + delta_col) % self.width;
-                let idx = self.get_index(neighbor_row, neighbor_col);
+    /// Returns the state of the cell at an index: 0 when dead, 1 when alive
+    /// and 2 or more when dying under a Generations rule
+    fn state_at(&self, idx: usize) -> u8 {
+        if self.cells[idx] {
+            1
+        } else {
+            self.dying.get(idx).copied().unwrap_or(0)
+        }
+    }
+
+    /// Sets the cell at an index to be alive or dead, ending any refractory
+    /// state it was in
+    fn write_cell(&mut self, idx: usize, alive: bool) {
+        self.cells.set(idx, alive);
+        if let Some(state) = self.dying.get_mut(idx) {
+            *state = 0;
+        }
+    }
+
+    /// Sets the state of the cell at an index, which must be one of the
+    /// rule's states
+    fn write_state(&mut self, idx: usize, state: u8) {
+        self.write_cell(idx, state == 1);
+        if state > 1 {
+            self.dying.resize(self.cells.len(), 0);
+            self.dying[idx] = state;
+        }
+    }
+
+    /// Applies the refractory states of a Generations rule to the next
+    /// state of the cells computed by the kernel
+    ///
+    /// Dying cells cannot be born and move on to their next state, while
+    /// live cells that did not survive start dying.
+    ///
+    /// # Arguments
+    ///
+    /// * `next` - The next live cells, as if the rule had two states
+    fn step_dying(&mut self, next: &mut FixedBitSet) {
+        let states = self.rule.states();
+        if states <= 2 {
+            self.dying.clear();
+            return;
+        }
 
-                count += self.cells[idx] as u8;
+        self.dying.resize(self.cells.len(), 0);
+        for (idx, state) in self.dying.iter_mut().enumerate() {
+            if *state != 0 {
+                next.set(idx, false);
+                *state = if *state + 1 < states { *state + 1 } else { 0 };
+            } else if self.cells[idx] && !next[idx] {
+                *state = 2;
             }
         }
-        count
     }
 
-    /// Draws a square pattern centered around a provided origin.
+    /// Returns the index of the cell a possibly out of range coordinate
+    /// refers to under the universe's topology
     ///
     /// # Arguments
     ///
-    /// * `origin_row` - The row coordinate of the pattern center
-    /// * `origin_column` - The column coordinate of the pattern center
-    /// * `size` - The size of the square pattern (must be odd)
-    /// * `alive_cells` - Vector of (row, col) coordinates for cells that should be alive
+    /// * `row` - The row coordinate, which may lie outside of the universe
+    /// * `column` - The column coordinate, which may lie outside of the universe
     ///
-    /// # Panics
+    /// # Returns
     ///
-    /// The size parameter must be less than or equal to both dimensions
-    /// of the universe, and must be an odd value in order for the square
-    /// to have a valid origin.
-    /// This function will panic if either of these conditions are not met.
-    fn draw_square_pattern(
-        &mut self,
-        origin_row: u32,
-        origin_column: u32,
-        size: u32,
-        alive_cells: &[(u32, u32)],
-    ) {
-        if size % 2 == 0 || size > self.width || size > self.height {
-            panic!("Size must be an odd value and smaller than the universe dimensions")
-        }
+    /// `None` if the coordinate lies past an edge that is not joined
+    fn wrapped_index(&self, row: i64, column: i64) -> Option<usize> {
+        self.topology
+            .wrap(self.width, self.height, row, column)
+            .map(|(row, column)| self.get_index(row, column))
+    }
 
-        let delta = size / 2;
-        let lower_delta_row_range: Vec<u32> = (self.height - delta..self.height).collect();
-        let lower_delta_col_range: Vec<u32> = (self.width - delta..self.width).collect();
-        let higher_delta_range: Vec<u32> = (0..delta + 1).collect();
+    /// Sets cells alive at offsets from a provided origin, following the
+    /// universe's topology for cells that extend past an edge
+    ///
+    /// # Arguments
+    ///
+    /// * `origin_row` - The row coordinate the offsets are relative to
+    /// * `origin_column` - The column coordinate the offsets are relative to
+    /// * `offsets` - (row, col) offsets of the cells to set alive
+    fn draw_cells(&mut self, origin_row: u32, origin_column: u32, offsets: &[(i64, i64)]) {
+        for (delta_row, delta_col) in offsets.iter().cloned() {
+            // Cells whose coordinates do not fit are skipped
+            let (Some(cell_row), Some(cell_col)) = (
+                (origin_row as i64).checked_add(delta_row),
+                (origin_column as i64).checked_add(delta_col),
+            ) else {
+                continue;
+            };
+            if let Some(idx) = self.wrapped_index(cell_row, cell_col) {
+                self.write_cell(idx, true);
+            }
+        }
+    }
 
-        for delta_row in lower_delta_row_range
-            .iter()
-            .chain(higher_delta_range.iter())
-        {
-            for delta_col in lower_delta_col_range
-                .iter()
-                .chain(higher_delta_range.iter())
-            {
-                let cell_row = (origin_row + delta_row) % self.height;
-                let cell_col = (origin_column + delta_col) % self.width;
-                let idx = self.get_index(cell_row, cell_col);
-
-                if alive_cells.contains(&(cell_row, cell_col)) {
-                    self.cells.set(idx, true);
-                } else {
-                    self.cells.set(idx, false);
+    /// Pastes a pattern with its top left corner at a coordinate that may
+    /// lie outside of the universe, see [`Universe::paste`]
+    fn paste_at(&mut self, pattern: &Pattern, row: i64, column: i64, mode: PasteMode) {
+        let states = self.rule.states();
+        for r in 0..pattern.height() {
+            for c in 0..pattern.width() {
+                let Some(idx) = self.wrapped_index(row + r as i64, column + c as i64) else {
+                    continue;
+                };
+                let state = pattern.cell_state(r, c);
+                let state = if state < states { state } else { 0 };
+                if let Some(state) = mode.combine(state, self.state_at(idx)) {
+                    self.write_state(idx, state);
                 }
             }
         }
     }
+
+    /// Pastes a pattern in an orientation and phase with the top left
+    /// corner of the oriented pattern at a coordinate that may lie outside
+    /// of the universe
+    ///
+    /// The oriented pattern is advanced `phase` generations under the
+    /// universe's rule on a plane before it is pasted, so its cells end up
+    /// where they would be after that many generations. Only the bounding
+    /// box of its cells that are not dead is pasted.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The pattern to paste
+    /// * `row` - The row of the oriented pattern's top left corner
+    /// * `column` - The column of the oriented pattern's top left corner
+    /// * `orientation` - The rotation or reflection of the pattern
+    /// * `phase` - The number of generations to advance the pattern
+    /// * `mode` - How the pattern's cells are combined with the universe's
+    ///
+    /// # Returns
+    ///
+    /// An error if `phase` is larger than [`pattern::MAX_PHASE`], or the
+    /// pattern grows too large for a universe while it is advanced
+    fn place(
+        &mut self,
+        pattern: &Pattern,
+        row: i64,
+        column: i64,
+        orientation: Transform,
+        phase: u32,
+        mode: PasteMode,
+    ) -> Result<(), LifeError> {
+        if phase > pattern::MAX_PHASE {
+            return Err(LifeError::PhaseTooLarge {
+                phase,
+                max: pattern::MAX_PHASE,
+            });
+        }
+        let pattern = pattern.transformed(orientation);
+        if phase == 0 {
+            self.paste_at(&pattern, row, column, mode);
+            return Ok(());
+        }
+
+        // Cells spread at most one neighborhood range per generation, and
+        // a saturated size is rejected as too large
+        let margin = phase * self.rule.neighborhood().range();
+        let mut scratch = Universe::try_with_rule(
+            pattern.width().saturating_add(2 * margin),
+            pattern.height().saturating_add(2 * margin),
+            self.rule,
+        )?;
+        scratch.set_topology(Topology::Plane);
+        scratch.paste_at(&pattern, margin as i64, margin as i64, PasteMode::Copy);
+        scratch.step(phase as u64);
+
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for (idx, state) in scratch.cell_states().into_iter().enumerate() {
+            if state != 0 {
+                let (r, c) = (idx as u32 / scratch.width, idx as u32 % scratch.width);
+                let (top, left, bottom, right) = bounds.unwrap_or((r, c, r, c));
+                bounds = Some((top.min(r), left.min(c), bottom.max(r), right.max(c)));
+            }
+        }
+        if let Some((top, left, bottom, right)) = bounds {
+            let advanced = scratch.copy_region(top, left, right - left + 1, bottom - top + 1);
+            let row = row - margin as i64 + top as i64;
+            let column = column - margin as i64 + left as i64;
+            self.paste_at(&advanced, row, column, mode);
+        }
+        Ok(())
+    }
+
+    /// Places a built-in pattern, given as the offsets of its live cells
+    /// from its center, with the center of the oriented pattern on a cell
+    ///
+    /// Only the bounding box of the live cells is placed, see
+    /// [`Universe::place`] for how it is oriented and advanced.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate of the pattern center
+    /// * `column` - The column coordinate of the pattern center
+    /// * `offsets` - (row, col) offsets from the center of the live cells
+    /// * `orientation` - The rotation or reflection of the pattern
+    /// * `phase` - The number of generations to advance the pattern
+    /// * `mode` - How the pattern's cells are combined with the universe's
+    fn place_offsets(
+        &mut self,
+        row: u32,
+        column: u32,
+        offsets: &[(i64, i64)],
+        orientation: Transform,
+        phase: u32,
+        mode: PasteMode,
+    ) -> Result<(), LifeError> {
+        let (pattern, (center_row, center_col)) = Pattern::from_offsets(offsets);
+        let (center_row, center_col) =
+            orientation.apply(center_row, center_col, pattern.width(), pattern.height());
+        self.place(
+            &pattern,
+            row as i64 - center_row as i64,
+            column as i64 - center_col as i64,
+            orientation,
+            phase,
+            mode,
+        )
+    }
+
+    /// Reads the (row, col) pairs of a JavaScript array of cells
+    ///
+    /// # Arguments
+    ///
+    /// * `alive_cells` - An array of `[row, column]` arrays
+    #[cfg(feature = "wasm")]
+    fn cells_from_array(alive_cells: &js_sys::Array) -> Result<Vec<(u32, u32)>, LifeError> {
+        let coordinate = |value: wasm_bindgen::JsValue| {
+            value
+                .as_f64()
+                .filter(|x| x.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(x))
+                .map(|x| x as u32)
+        };
+
+        (0..alive_cells.length())
+            .map(|i| {
+                alive_cells
+                    .get(i)
+                    .dyn_into::<js_sys::Array>()
+                    .ok()
+                    .filter(|cell_array| cell_array.length() == 2)
+                    .and_then(|cell_array| {
+                        Some((
+                            coordinate(cell_array.get(0))?,
+                            coordinate(cell_array.get(1))?,
+                        ))
+                    })
+                    .ok_or(LifeError::InvalidCell { index: i })
+            })
+            .collect()
+    }
 }
 
 /// Public methods for the universe
@@ -124,18 +381,43 @@ impl Universe {
     ///
     /// * `width` - The width of the universe
     /// * `height` - The height of the universe
+    ///
+    /// # Panics
+    ///
+    /// Panics if the universe would have more than 2^30 cells, see
+    /// [`Universe::try_new_empty`] for a fallible version.
     pub fn new_empty(width: u32, height: u32) -> Universe {
+        Self::try_new_empty(width, height).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new empty Universe with specified dimensions, if it is not
+    /// too large
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the universe
+    /// * `height` - The height of the universe
+    ///
+    /// # Returns
+    ///
+    /// An error if the universe would have more than 2^30 cells
+    pub fn try_new_empty(width: u32, height: u32) -> Result<Universe, LifeError> {
         #[cfg(feature = "wasm")]
         utils::set_panic_hook();
 
-        let size = (width * height) as usize;
+        let size = Self::cell_count(width, height)?;
         let cells = FixedBitSet::with_capacity(size);
 
-        Universe {
+        Ok(Universe {
             width,
             height,
             cells,
-        }
+            dying: Vec::new(),
+            rule: Rule::conway(),
+            topology: Topology::Torus,
+            generation: 0,
+            buffers: kernel::TickBuffers::default(),
+        })
     }
 
     /// Creates a new Universe with random cells and specified dimensions
@@ -152,46 +434,73 @@ impl Universe {
 
     /// Updates the universe's cells for a single tick
     ///
-    /// Implements rules based on Conway's Game of Life:
+    /// Each cell's next state is decided by the universe's rule from its
+    /// current state and its number of live neighbors. With the default
+    /// rules of Conway's Game of Life (`B3/S23`):
     /// - Any live cell with fewer than two live neighbors dies (underpopulation)
     /// - Any live cell with two or three live neighbors lives to the next generation
     /// - Any live cell with more than three live neighbors dies (overpopulation)
     /// - Any dead cell with exactly three live neighbors becomes a live cell (reproduction)
+    ///
+    /// Cells are updated 64 at a time by the bit-parallel kernel, which
+    /// writes into a second buffer that is swapped with the current cells.
+    /// Larger than Life rules count their neighbors with summed-area tables
+    /// instead.
     pub fn tick(&mut self) {
-        let mut next = self.cells.clone();
-
-        for row in 0..self.height {
-            for col in 0..self.width {
-                let idx = self.get_index(row, col);
-                let cell = self.cells[idx];
-                let live_neighbors = self.live_neighbor_count(row, col);
-
-                let next_cell = match (cell, live_neighbors) {
-                    // Rule 1: Any live cell with fewer than two live neighbors
-                    // dies, as if caused by underpopulation
-                    (true, x) if x < 2 => false,
-
-                    // Rule 2: Any live cell with two or three live neighbors
-                    // lives to the next generation
-                    (true, 2) | (true, 3) => true,
+        let mut buffers = std::mem::take(&mut self.buffers);
+        if self.rule.is_larger_than_life() {
+            extended::step(self, &mut buffers);
+        } else {
+            kernel::step(self, &mut buffers);
+        }
+        self.step_dying(&mut buffers.next);
+        std::mem::swap(&mut self.cells, &mut buffers.next);
+        self.buffers = buffers;
 
-                    // Rule 3: Any live cell with more than three live neighbors
-                    // dies, as if by overpopulation
-                    (true, x) if x > 3 => false,
+        self.generation += 1;
+    }
 
-                    // Rule 4: Any dead cell with exactly three live neighbors
-                    // becomes a live cell, as if by reproduction
-                    (false, 3) => true,
+    /// Sets the rule used to update the universe from a rulestring
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - A rulestring such as `B3/S23`, `B36/S23`, `23/3`, a
+    ///   Generations rule such as `B2/S/C3`, a hexagonal rule such as
+    ///   `B2/S34H` or a Larger than Life rule such as
+    ///   `R5,C0,M1,S34..58,B34..45,NM`
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), RuleParseError> {
+        self.rule = Rule::parse(rule)?;
+
+        // Dying cells past the new rule's last state are dead
+        let states = self.rule.states();
+        for state in self.dying.iter_mut().filter(|state| **state >= states) {
+            *state = 0;
+        }
+        Ok(())
+    }
 
-                    // All other cells remain in the same state
-                    (otherwise, _) => otherwise,
-                };
+    /// Returns the rule used to update the universe as a rulestring
+    pub fn rulestring(&self) -> String {
+        self.rule.to_string()
+    }
 
-                next.set(idx, next_cell);
-            }
-        }
+    /// Sets the topology of the universe from its name
+    ///
+    /// # Arguments
+    ///
+    /// * `topology` - One of `torus`, `plane`, `cylinder-horizontal`,
+    ///   `cylinder-vertical`, `klein-bottle`, `cross-surface` or
+    ///   `shifted-torus:<shift>`
+    #[cfg(feature = "wasm")]
+    pub fn set_topology_wasm(&mut self, topology: &str) -> Result<(), TopologyParseError> {
+        self.topology = topology.parse()?;
+        Ok(())
+    }
 
-        self.cells = next;
+    /// Returns the name of the universe's topology
+    #[cfg(feature = "wasm")]
+    pub fn topology_wasm(&self) -> String {
+        self.topology.to_string()
     }
 
     /// Sets every cell in the universe to be dead
@@ -201,6 +510,7 @@ impl Universe {
             cells.set(i, false);
         }
         self.cells = cells;
+        self.dying.clear();
     }
 
     /// Sets every cell's value randomly with a 50% chance of being alive or dead
@@ -213,6 +523,7 @@ impl Universe {
         }
 
         self.cells = cells;
+        self.dying.clear();
     }
 
     /// Renders the state of the universe as a string
@@ -220,6 +531,185 @@ impl Universe {
         self.to_string()
     }
 
+    /// Creates a new Universe from a pattern in any supported format, which
+    /// is detected from its contents
+    ///
+    /// Patterns without dimensions, such as Life 1.06, are cropped to their
+    /// live cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of a pattern file
+    pub fn from_pattern(text: &str) -> Result<Universe, LifeError> {
+        let universe = match PatternFormat::detect(text) {
+            PatternFormat::Rle => Self::from_rle(text)?,
+            PatternFormat::Plaintext => Self::from_plaintext(text)?,
+            PatternFormat::Life105 => Self::from_life105(text)?,
+            PatternFormat::Life106 => Self::from_life106(text)?,
+            PatternFormat::Macrocell => Self::from_macrocell(text)?,
+        };
+        Ok(universe)
+    }
+
+    /// Creates a new Universe from a pattern in RLE format
+    ///
+    /// The universe is sized to the `x` and `y` dimensions of the RLE header
+    /// and uses the header's rule, or Conway's Game of Life if none is given.
+    ///
+    /// # Arguments
+    ///
+    /// * `rle` - The contents of an RLE file
+    pub fn from_rle(rle: &str) -> Result<Universe, RleError> {
+        formats::rle::parse(rle)
+    }
+
+    /// Encodes the whole universe as a pattern in RLE format
+    pub fn to_rle(&self) -> String {
+        formats::rle::write(self)
+    }
+
+    /// Creates a new Universe from a pattern in plaintext (`.cells`) format
+    ///
+    /// The universe is sized to the longest row and the number of rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of a `.cells` file
+    pub fn from_plaintext(text: &str) -> Result<Universe, PatternParseError> {
+        let pattern = formats::plaintext::parse(text)?;
+        let mut universe = Self::new_empty(pattern.width, pattern.height);
+        universe.draw_cells(0, 0, &pattern.alive_cells);
+        Ok(universe)
+    }
+
+    /// Sets the live cells of a plaintext (`.cells`) pattern alive, with the
+    /// pattern's top left corner at the given cell
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of a `.cells` file
+    /// * `row` - The row coordinate of the pattern's top left corner
+    /// * `column` - The column coordinate of the pattern's top left corner
+    pub fn load_plaintext(
+        &mut self,
+        text: &str,
+        row: u32,
+        column: u32,
+    ) -> Result<(), PatternParseError> {
+        let pattern = formats::plaintext::parse(text)?;
+        self.draw_cells(row, column, &pattern.alive_cells);
+        Ok(())
+    }
+
+    /// Encodes the whole universe as a pattern in plaintext (`.cells`) format
+    pub fn to_plaintext(&self) -> String {
+        formats::plaintext::write(self)
+    }
+
+    /// Creates a new Universe from a Life 1.06 pattern, cropped to its live
+    /// cells
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of a Life 1.06 file
+    pub fn from_life106(text: &str) -> Result<Universe, PatternParseError> {
+        formats::cropped_universe(Rule::default(), &formats::life::parse_106(text)?)
+    }
+
+    /// Sets the live cells of a Life 1.06 pattern alive, with the pattern's
+    /// origin at the given cell
+    ///
+    /// Cells follow the universe's topology past its edges, and cells whose
+    /// coordinates from the origin do not fit in 64 bits are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of a Life 1.06 file
+    /// * `row` - The row coordinate of the pattern's origin
+    /// * `column` - The column coordinate of the pattern's origin
+    pub fn load_life106(
+        &mut self,
+        text: &str,
+        row: u32,
+        column: u32,
+    ) -> Result<(), PatternParseError> {
+        let alive_cells = formats::life::parse_106(text)?;
+        self.draw_cells(row, column, &alive_cells);
+        Ok(())
+    }
+
+    /// Encodes the live cells of the universe as a Life 1.06 pattern, with
+    /// the top left cell of the universe as the origin
+    pub fn to_life106(&self) -> String {
+        formats::life::write_106(self)
+    }
+
+    /// Creates a new Universe from a Life 1.05 pattern, cropped to its live
+    /// cells
+    ///
+    /// The universe uses the rule named by a `#N` or `#R` line, or Conway's
+    /// Game of Life if none is given.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of a Life 1.05 file
+    pub fn from_life105(text: &str) -> Result<Universe, PatternParseError> {
+        let pattern = formats::life::parse_105(text)?;
+        formats::cropped_universe(pattern.rule.unwrap_or_default(), &pattern.alive_cells)
+    }
+
+    /// Sets the live cells of a Life 1.05 pattern alive, with the pattern's
+    /// origin at the given cell
+    ///
+    /// A `#N` or `#R` line in the pattern replaces the universe's rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of a Life 1.05 file
+    /// * `row` - The row coordinate of the pattern's origin
+    /// * `column` - The column coordinate of the pattern's origin
+    pub fn load_life105(
+        &mut self,
+        text: &str,
+        row: u32,
+        column: u32,
+    ) -> Result<(), PatternParseError> {
+        let pattern = formats::life::parse_105(text)?;
+        if let Some(rule) = pattern.rule {
+            self.rule = rule;
+        }
+        self.draw_cells(row, column, &pattern.alive_cells);
+        Ok(())
+    }
+
+    /// Encodes the live cells of the universe as a Life 1.05 pattern, with
+    /// the top left cell of the universe as the origin
+    pub fn to_life105(&self) -> String {
+        formats::life::write_105(self)
+    }
+
+    /// Creates a new Universe from a pattern in Golly's macrocell (`.mc`)
+    /// format
+    ///
+    /// The universe is cropped to the live cells and uses the pattern's rule
+    /// and generation. Patterns too large for a universe can be loaded with
+    /// [`HashLife::from_macrocell`] instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of a `.mc` file
+    pub fn from_macrocell(text: &str) -> Result<Universe, PatternParseError> {
+        formats::macrocell::parse_universe(text)
+    }
+
+    /// Encodes the live cells of the universe in Golly's macrocell (`.mc`)
+    /// format, with the top left cell of the universe as the origin
+    ///
+    /// Refractory states of Generations rules are not written.
+    pub fn to_macrocell(&self) -> String {
+        formats::macrocell::write_universe(self)
+    }
+
     /// Returns the width of the universe
     pub fn width(&self) -> u32 {
         self.width
@@ -227,13 +717,21 @@ impl Universe {
 
     /// Sets the width of the universe and resets all cells
     ///
+    /// Use [`Universe::resize`] to keep the cells instead.
+    ///
     /// # Arguments
     ///
     /// * `width` - The new width of the universe
+    ///
+    /// # Panics
+    ///
+    /// Panics if the universe would have more than 2^30 cells, see
+    /// [`Universe::try_resize`] for a fallible way to resize.
     pub fn set_width(&mut self, width: u32) {
+        let size = Self::cell_count(width, self.height).unwrap_or_else(|error| panic!("{}", error));
         self.width = width;
-        let size = (width * self.height) as usize;
         self.cells = FixedBitSet::with_capacity(size);
+        self.dying.clear();
     }
 
     /// Returns the height of the universe
@@ -243,13 +741,348 @@ impl Universe {
 
     /// Sets the height of the universe and resets all cells
     ///
+    /// Use [`Universe::resize`] to keep the cells instead.
+    ///
     /// # Arguments
     ///
     /// * `height` - The new height of the universe
+    ///
+    /// # Panics
+    ///
+    /// Panics if the universe would have more than 2^30 cells, see
+    /// [`Universe::try_resize`] for a fallible way to resize.
     pub fn set_height(&mut self, height: u32) {
+        let size = Self::cell_count(self.width, height).unwrap_or_else(|error| panic!("{}", error));
         self.height = height;
-        let size = (self.width * height) as usize;
         self.cells = FixedBitSet::with_capacity(size);
+        self.dying.clear();
+    }
+
+    /// Resizes the universe while keeping its cells in place relative to an
+    /// anchor
+    ///
+    /// Growing the universe adds dead cells on the sides away from the
+    /// anchor, and shrinking it crops the cells on those sides. The rule,
+    /// topology, generation and refractory states are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The new width of the universe
+    /// * `height` - The new height of the universe
+    /// * `anchor` - The point of the universe that stays in place, such as
+    ///   its top left corner or its center
+    ///
+    /// # Panics
+    ///
+    /// Panics if the universe would have more than 2^30 cells, see
+    /// [`Universe::try_resize`] for a fallible version.
+    pub fn resize(&mut self, width: u32, height: u32, anchor: Anchor) {
+        self.try_resize(width, height, anchor)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Resizes the universe while keeping its cells in place relative to an
+    /// anchor, if the resized universe is not too large
+    ///
+    /// See [`Universe::resize`] for the arguments.
+    ///
+    /// # Returns
+    ///
+    /// An error if the universe would have more than 2^30 cells, in which
+    /// case it is left unchanged
+    pub fn try_resize(&mut self, width: u32, height: u32, anchor: Anchor) -> Result<(), LifeError> {
+        let (row_offset, col_offset) = anchor.offset((self.width, self.height), (width, height));
+        let mut resized = Universe::try_with_rule(width, height, self.rule)?;
+        resized.topology = self.topology;
+        resized.generation = self.generation;
+
+        for row in 0..self.height {
+            let new_row = row as i64 + row_offset;
+            if !(0..height as i64).contains(&new_row) {
+                continue;
+            }
+            for col in 0..self.width {
+                let new_col = col as i64 + col_offset;
+                let state = self.state_at(self.get_index(row, col));
+                if state != 0 && (0..width as i64).contains(&new_col) {
+                    let idx = resized.get_index(new_row as u32, new_col as u32);
+                    resized.write_state(idx, state);
+                }
+            }
+        }
+        *self = resized;
+        Ok(())
+    }
+
+    /// Returns a copy of the universe rotated or reflected as a whole
+    ///
+    /// Quarter turns and transposes of a universe that is not square swap
+    /// its width and height. The rule, topology, generation and refractory
+    /// states are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The rotation or reflection to apply
+    pub fn transformed(&self, transform: Transform) -> Universe {
+        let (width, height) = transform.size(self.width, self.height);
+        let mut transformed = Universe::with_rule(width, height, self.rule);
+        transformed.topology = self.topology;
+        transformed.generation = self.generation;
+
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let state = self.state_at(self.get_index(row, col));
+                if state != 0 {
+                    let (new_row, new_col) = transform.apply(row, col, self.width, self.height);
+                    let idx = transformed.get_index(new_row, new_col);
+                    transformed.write_state(idx, state);
+                }
+            }
+        }
+        transformed
+    }
+
+    /// Rotates or reflects the whole universe in place
+    ///
+    /// See [`Universe::transformed`] for how the dimensions change.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The rotation or reflection to apply
+    pub fn transform(&mut self, transform: Transform) {
+        *self = self.transformed(transform);
+    }
+
+    /// Rotates or reflects a rectangular region of the universe in place
+    ///
+    /// The transformed region keeps its top left corner, so quarter turns
+    /// and transposes of a region that is not square cover a rectangle with
+    /// its width and height swapped. The cells of the original region are
+    /// cleared before the transformed cells are written.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The top row of the region
+    /// * `column` - The left column of the region
+    /// * `width` - The width of the region
+    /// * `height` - The height of the region
+    /// * `transform` - The rotation or reflection to apply
+    ///
+    /// # Returns
+    ///
+    /// An error if the region or the transformed region extends outside of
+    /// the universe, in which case no cells are changed
+    pub fn transform_region(
+        &mut self,
+        row: u32,
+        column: u32,
+        width: u32,
+        height: u32,
+        transform: Transform,
+    ) -> Result<(), LifeError> {
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let (new_width, new_height) = transform.size(width, height);
+        self.checked_index(
+            row.saturating_add(height.max(new_height) - 1),
+            column.saturating_add(width.max(new_width) - 1),
+        )?;
+
+        let mut states = Vec::with_capacity((width * height) as usize);
+        for r in 0..height {
+            for c in 0..width {
+                let idx = self.get_index(row + r, column + c);
+                states.push(self.state_at(idx));
+                self.write_state(idx, 0);
+            }
+        }
+        for r in 0..height {
+            for c in 0..width {
+                let state = states[(r * width + c) as usize];
+                if state != 0 {
+                    let (new_r, new_c) = transform.apply(r, c, width, height);
+                    let idx = self.get_index(row + new_r, column + new_c);
+                    self.write_state(idx, state);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of the universe with every cell moved by an offset
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The number of rows to move the cells down, or up when
+    ///   negative
+    /// * `columns` - The number of columns to move the cells right, or left
+    ///   when negative
+    /// * `wrap` - Whether cells moved past an edge reappear on the opposite
+    ///   edge, rather than being removed
+    pub fn translated(&self, rows: i32, columns: i32, wrap: bool) -> Universe {
+        let mut translated = Universe::with_rule(self.width, self.height, self.rule);
+        translated.topology = self.topology;
+        translated.generation = self.generation;
+        let (height, width) = (self.height as i64, self.width as i64);
+
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let state = self.state_at(self.get_index(row, col));
+                if state == 0 {
+                    continue;
+                }
+                let mut new_row = row as i64 + rows as i64;
+                let mut new_col = col as i64 + columns as i64;
+                if wrap {
+                    new_row = new_row.rem_euclid(height);
+                    new_col = new_col.rem_euclid(width);
+                } else if !(0..height).contains(&new_row) || !(0..width).contains(&new_col) {
+                    continue;
+                }
+                let idx = translated.get_index(new_row as u32, new_col as u32);
+                translated.write_state(idx, state);
+            }
+        }
+        translated
+    }
+
+    /// Moves every cell of the universe by an offset
+    ///
+    /// See [`Universe::translated`] for the arguments.
+    pub fn translate(&mut self, rows: i32, columns: i32, wrap: bool) {
+        *self = self.translated(rows, columns, wrap);
+    }
+
+    /// Copies a rectangular region of the universe into a pattern
+    ///
+    /// Cells of the region that lie past an edge follow the universe's
+    /// topology, and are copied as dead cells when that edge is not joined.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The top row of the region
+    /// * `column` - The left column of the region
+    /// * `width` - The width of the region
+    /// * `height` - The height of the region
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region has more than 2^30 cells, see
+    /// [`Universe::try_copy_region`] for a fallible version.
+    pub fn copy_region(&self, row: u32, column: u32, width: u32, height: u32) -> Pattern {
+        self.try_copy_region(row, column, width, height)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Copies a rectangular region of the universe into a pattern, if the
+    /// region is not too large
+    ///
+    /// See [`Universe::copy_region`] for the arguments.
+    ///
+    /// # Returns
+    ///
+    /// An error if the region has more than 2^30 cells
+    pub fn try_copy_region(
+        &self,
+        row: u32,
+        column: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<Pattern, LifeError> {
+        let mut pattern = Pattern::try_new(width, height)?;
+        for r in 0..height {
+            for c in 0..width {
+                if let Some(idx) =
+                    self.wrapped_index(row as i64 + r as i64, column as i64 + c as i64)
+                {
+                    pattern.set_cell_state(r, c, self.state_at(idx));
+                }
+            }
+        }
+        Ok(pattern)
+    }
+
+    /// Copies a rectangular region of the universe into a pattern and kills
+    /// its cells
+    ///
+    /// See [`Universe::copy_region`] for the arguments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region has more than 2^30 cells, see
+    /// [`Universe::try_cut_region`] for a fallible version.
+    pub fn cut_region(&mut self, row: u32, column: u32, width: u32, height: u32) -> Pattern {
+        self.try_cut_region(row, column, width, height)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Copies a rectangular region of the universe into a pattern and kills
+    /// its cells, if the region is not too large
+    ///
+    /// See [`Universe::copy_region`] for the arguments.
+    ///
+    /// # Returns
+    ///
+    /// An error if the region has more than 2^30 cells, in which case no
+    /// cell is killed
+    pub fn try_cut_region(
+        &mut self,
+        row: u32,
+        column: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<Pattern, LifeError> {
+        let pattern = self.try_copy_region(row, column, width, height)?;
+        for r in 0..height {
+            for c in 0..width {
+                if let Some(idx) =
+                    self.wrapped_index(row as i64 + r as i64, column as i64 + c as i64)
+                {
+                    self.write_state(idx, 0);
+                }
+            }
+        }
+        Ok(pattern)
+    }
+
+    /// Pastes a pattern into the universe with the top left corner of the
+    /// oriented pattern at a provided cell
+    ///
+    /// Cells of the pattern that lie past an edge follow the universe's
+    /// topology and are skipped when that edge is not joined. Refractory
+    /// states that the universe's rule does not have are pasted as dead
+    /// cells.
+    ///
+    /// A pattern pasted in a later phase is advanced under the universe's
+    /// rule first, keeping its cells where they would be after that many
+    /// generations, and only the bounding box of its cells that are not
+    /// dead is pasted.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The pattern to paste
+    /// * `row` - The row of the oriented pattern's top left corner
+    /// * `column` - The column of the oriented pattern's top left corner
+    /// * `orientation` - The rotation or reflection of the pattern
+    /// * `phase` - The number of generations to advance the pattern, or 0
+    ///   to paste it as it is
+    /// * `mode` - How the pattern's cells are combined with the universe's
+    ///
+    /// # Returns
+    ///
+    /// An error if `phase` is more than 1024 generations, or the pattern
+    /// grows too large for a universe while it is advanced
+    pub fn paste(
+        &mut self,
+        pattern: &Pattern,
+        row: u32,
+        column: u32,
+        orientation: Transform,
+        phase: u32,
+        mode: PasteMode,
+    ) -> Result<(), LifeError> {
+        self.place(pattern, row as i64, column as i64, orientation, phase, mode)
     }
 
     /// Returns a pointer to the cells data for WASM interop
@@ -265,9 +1098,28 @@ impl Universe {
     /// * `row` - The row coordinate
     /// * `column` - The column coordinate
     /// * `alive` - Whether the cell should be alive or dead
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell lies outside of the universe, see
+    /// [`Universe::try_set_cell`] for a fallible version.
     pub fn set_cell(&mut self, row: u32, column: u32, alive: bool) {
-        let idx = self.get_index(row, column);
-        self.cells.set(idx, alive);
+        self.try_set_cell(row, column, alive)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Set a cell to be alive or dead at the given coordinates, if they lie
+    /// inside the universe
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate
+    /// * `column` - The column coordinate
+    /// * `alive` - Whether the cell should be alive or dead
+    pub fn try_set_cell(&mut self, row: u32, column: u32, alive: bool) -> Result<(), LifeError> {
+        let idx = self.checked_index(row, column)?;
+        self.write_cell(idx, alive);
+        Ok(())
     }
 
     /// Check if a cell is alive at the given coordinates
@@ -280,46 +1132,130 @@ impl Universe {
     /// # Returns
     ///
     /// `true` if the cell is alive, `false` otherwise
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell lies outside of the universe, see
+    /// [`Universe::try_is_cell_alive`] for a fallible version.
     pub fn is_cell_alive(&self, row: u32, column: u32) -> bool {
-        let idx = self.get_index(row, column);
-        self.cells[idx]
+        self.try_is_cell_alive(row, column)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
-    // WASM-specific methods for pattern creation
-    #[cfg(feature = "wasm")]
-    pub fn new_with_pattern_wasm(width: u32, height: u32, alive_cells: js_sys::Array) -> Universe {
-        let mut universe = Self::new_empty(width, height);
+    /// Check if a cell is alive at the given coordinates, if they lie inside
+    /// the universe
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate
+    /// * `column` - The column coordinate
+    ///
+    /// # Returns
+    ///
+    /// `true` if the cell is alive, `false` otherwise
+    pub fn try_is_cell_alive(&self, row: u32, column: u32) -> Result<bool, LifeError> {
+        let idx = self.checked_index(row, column)?;
+        Ok(self.cells[idx])
+    }
 
-        for i in 0..alive_cells.length() {
-            if let Ok(cell_array) = alive_cells.get(i).dyn_into::<js_sys::Array>() {
-                if cell_array.length() == 2 {
-                    if let (Some(row), Some(col)) = (
-                        cell_array.get(0).as_f64().map(|x| x as u32),
-                        cell_array.get(1).as_f64().map(|x| x as u32),
-                    ) {
-                        universe.set_cell(row, col, true);
-                    }
-                }
-            }
+    /// Returns the state of a cell at the given coordinates
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate
+    /// * `column` - The column coordinate
+    ///
+    /// # Returns
+    ///
+    /// 0 if the cell is dead, 1 if it is alive, or its refractory state from
+    /// 2 up to the number of states of a Generations rule if it is dying
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell lies outside of the universe, see
+    /// [`Universe::try_cell_state`] for a fallible version.
+    pub fn cell_state(&self, row: u32, column: u32) -> u8 {
+        self.try_cell_state(row, column)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Returns the state of a cell at the given coordinates, if they lie
+    /// inside the universe
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate
+    /// * `column` - The column coordinate
+    pub fn try_cell_state(&self, row: u32, column: u32) -> Result<u8, LifeError> {
+        let idx = self.checked_index(row, column)?;
+        Ok(self.state_at(idx))
+    }
+
+    /// Sets the state of a cell at the given coordinates
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate
+    /// * `column` - The column coordinate
+    /// * `state` - 0 for dead, 1 for alive or a refractory state of the rule
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell lies outside of the universe or the state is not
+    /// one of the rule's states, see [`Universe::try_set_cell_state`] for a
+    /// fallible version.
+    pub fn set_cell_state(&mut self, row: u32, column: u32, state: u8) {
+        self.try_set_cell_state(row, column, state)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Sets the state of a cell at the given coordinates, if they lie inside
+    /// the universe and the state is one of the rule's states
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate
+    /// * `column` - The column coordinate
+    /// * `state` - 0 for dead, 1 for alive or a refractory state of the rule
+    pub fn try_set_cell_state(
+        &mut self,
+        row: u32,
+        column: u32,
+        state: u8,
+    ) -> Result<(), LifeError> {
+        let idx = self.checked_index(row, column)?;
+        let states = self.rule.states();
+        if state >= states {
+            return Err(LifeError::InvalidState { state, states });
         }
 
-        universe
+        self.write_state(idx, state);
+        Ok(())
     }
 
+    /// Returns the state of every cell, row by row, as given by
+    /// [`Universe::cell_state`]
+    pub fn cell_states(&self) -> Vec<u8> {
+        (0..self.cells.len())
+            .map(|idx| self.state_at(idx))
+            .collect()
+    }
+
+    // WASM-specific methods for pattern creation
     #[cfg(feature = "wasm")]
-    pub fn set_cells_wasm(&mut self, alive_cells: js_sys::Array) {
-        for i in 0..alive_cells.length() {
-            if let Ok(cell_array) = alive_cells.get(i).dyn_into::<js_sys::Array>() {
-                if cell_array.length() == 2 {
-                    if let (Some(row), Some(col)) = (
-                        cell_array.get(0).as_f64().map(|x| x as u32),
-                        cell_array.get(1).as_f64().map(|x| x as u32),
-                    ) {
-                        self.set_cell(row, col, true);
-                    }
-                }
-            }
-        }
+    pub fn new_with_pattern_wasm(
+        width: u32,
+        height: u32,
+        alive_cells: js_sys::Array,
+    ) -> Result<Universe, LifeError> {
+        let mut universe = Self::try_new_empty(width, height)?;
+        universe.try_set_cells(&Self::cells_from_array(&alive_cells)?)?;
+        Ok(universe)
+    }
+
+    #[cfg(feature = "wasm")]
+    pub fn set_cells_wasm(&mut self, alive_cells: js_sys::Array) -> Result<(), LifeError> {
+        self.try_set_cells(&Self::cells_from_array(&alive_cells)?)
     }
 
     // WASM-compatible method for testing - returns a Vec<u32> representation of cells
@@ -331,6 +1267,55 @@ impl Universe {
 
 // Non-WASM methods (available in both native and WASM builds)
 impl Universe {
+    /// Creates a new empty Universe with specified dimensions and rule
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the universe
+    /// * `height` - The height of the universe
+    /// * `rule` - The rule used to update the universe
+    ///
+    /// # Panics
+    ///
+    /// Panics if the universe would have more than 2^30 cells, see
+    /// [`Universe::try_with_rule`] for a fallible version.
+    pub fn with_rule(width: u32, height: u32, rule: Rule) -> Universe {
+        let mut universe = Self::new_empty(width, height);
+        universe.rule = rule;
+        universe
+    }
+
+    /// Creates a new empty Universe following the given rule, if it is not
+    /// too large
+    ///
+    /// # Returns
+    ///
+    /// An error if the universe would have more than 2^30 cells
+    pub fn try_with_rule(width: u32, height: u32, rule: Rule) -> Result<Universe, LifeError> {
+        let mut universe = Self::try_new_empty(width, height)?;
+        universe.rule = rule;
+        Ok(universe)
+    }
+
+    /// Returns the rule used to update the universe
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Returns the way the edges of the universe are joined together
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Sets the way the edges of the universe are joined together
+    ///
+    /// # Arguments
+    ///
+    /// * `topology` - The new topology of the universe
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
     /// Creates a new Universe with specified dimensions and initial pattern
     ///
     /// # Arguments
@@ -344,22 +1329,159 @@ impl Universe {
         universe
     }
 
-    /// Get the dead and alive values of the entire universe.
-    pub fn get_cells(&self) -> &FixedBitSet {
-        &self.cells
+    /// Runs a copy of the universe forward until its cells repeat, possibly
+    /// translated, to find the cycle it settles into
+    ///
+    /// Patterns that cross edges joined without a twist match their unsplit
+    /// copies. Across edges joined with a twist or shift, a pattern is only
+    /// matched in place, so the start of its cycle may be reported late.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_generations` - The number of generations to run before giving up
+    ///
+    /// # Returns
+    ///
+    /// The period of the cycle, the generation it began and whether it is
+    /// a still life, oscillator or spaceship, or `None` if no state repeated
+    /// within `max_generations`
+    pub fn find_cycle(&self, max_generations: u64) -> Option<Cycle> {
+        analysis::find_cycle(self, max_generations)
+    }
+
+    /// Encodes the universe as a pattern in the given format
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The pattern file format to write
+    pub fn to_pattern(&self, format: PatternFormat) -> String {
+        match format {
+            PatternFormat::Rle => self.to_rle(),
+            PatternFormat::Plaintext => self.to_plaintext(),
+            PatternFormat::Life105 => self.to_life105(),
+            PatternFormat::Life106 => self.to_life106(),
+            PatternFormat::Macrocell => self.to_macrocell(),
+        }
+    }
+
+    /// Returns the smallest rectangle containing every live cell
+    ///
+    /// # Returns
+    ///
+    /// The `(top, left, bottom, right)` coordinates of the rectangle, with
+    /// inclusive bounds, or `None` if there are no live cells
+    pub fn bounding_box(&self) -> Option<(u32, u32, u32, u32)> {
+        let width = self.width as usize;
+        let (first, last) = (self.cells.minimum()?, self.cells.maximum()?);
+        let (left, right) = self
+            .cells
+            .ones()
+            .map(|idx| idx % width)
+            .fold((width, 0), |(left, right), col| {
+                (left.min(col), right.max(col))
+            });
+        Some((
+            (first / width) as u32,
+            left as u32,
+            (last / width) as u32,
+            right as u32,
+        ))
+    }
+
+    /// Get the dead and alive values of the entire universe.
+    pub fn get_cells(&self) -> &FixedBitSet {
+        &self.cells
+    }
+
+    /// Set cells to be alive in a universe by passing the row and column
+    /// of each cell in an array
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - Vector of (row, col) coordinates for cells to set alive
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the cells lies outside of the universe, see
+    /// [`Universe::try_set_cells`] for a fallible version.
+    pub fn set_cells(&mut self, cells: &[(u32, u32)]) {
+        self.try_set_cells(cells)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Set cells to be alive in a universe by passing the row and column
+    /// of each cell in an array
+    ///
+    /// Every cell is checked before any are set, so the universe is left
+    /// unchanged when an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - Vector of (row, col) coordinates for cells to set alive
+    pub fn try_set_cells(&mut self, cells: &[(u32, u32)]) -> Result<(), LifeError> {
+        let indices = cells
+            .iter()
+            .map(|&(row, col)| self.checked_index(row, col))
+            .collect::<Result<Vec<_>, _>>()?;
+        for idx in indices {
+            self.write_cell(idx, true);
+        }
+        Ok(())
+    }
+
+    /// Renders the universe as an SVG image
+    ///
+    /// Horizontal runs of live cells are merged into single rectangles, so
+    /// the image stays small and scales to any print size.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The cell size, colors, gridlines and region to draw
+    /// * `highlighted` - The `(row, column)` of cells to outline in the
+    ///   highlight color
+    pub fn to_svg(&self, options: &ImageOptions, highlighted: &[(u32, u32)]) -> String {
+        image::write_svg(self, options, highlighted)
+    }
+
+    /// Renders the universe as a PNG image
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The cell size, colors, gridlines and region to draw
+    ///
+    /// # Returns
+    ///
+    /// The bytes of the PNG file, or an error if the image would have no
+    /// pixels or more than 2^28 pixels
+    #[cfg(feature = "png")]
+    pub fn to_png(&self, options: &ImageOptions) -> Result<Vec<u8>, LifeError> {
+        let image = image::render(self, options)?;
+        Ok(image::encode_png(&image, &options.palette()))
     }
 
-    /// Set cells to be alive in a universe by passing the row and column
-    /// of each cell in an array
+    /// Steps the universe and records it as an animated GIF
+    ///
+    /// The first frame shows the current generation, and another frame is
+    /// added after every `options.stride` generations.
     ///
     /// # Arguments
     ///
-    /// * `cells` - Vector of (row, col) coordinates for cells to set alive
-    pub fn set_cells(&mut self, cells: &[(u32, u32)]) {
-        for (row, col) in cells.iter().cloned() {
-            let idx = self.get_index(row, col);
-            self.cells.set(idx, true);
-        }
+    /// * `generations` - The number of generations to step
+    /// * `options` - The frame stride, delay, repeat count and how frames
+    ///   are drawn
+    ///
+    /// # Returns
+    ///
+    /// The bytes of the GIF file, or an error if `options.stride` is zero or
+    /// the frames would have no pixels, more than 2^28 pixels, or be wider
+    /// or taller than 65535 pixels
+    #[cfg(feature = "gif")]
+    pub fn record_gif(
+        &mut self,
+        generations: u32,
+        options: &GifOptions,
+    ) -> Result<Vec<u8>, LifeError> {
+        image::record_gif(self, generations, options)
     }
 }
 
@@ -372,9 +1494,26 @@ impl Universe {
     ///
     /// * `row` - The row coordinate of the cell to toggle
     /// * `column` - The column coordinate of the cell to toggle
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell lies outside of the universe, see
+    /// [`Universe::try_toggle_cell`] for a fallible version.
     pub fn toggle_cell(&mut self, row: u32, column: u32) {
-        let idx = self.get_index(row, column);
-        self.cells.set(idx, !self.cells[idx]);
+        self.try_toggle_cell(row, column)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Toggles the state of a single cell, if it lies inside the universe
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate of the cell to toggle
+    /// * `column` - The column coordinate of the cell to toggle
+    pub fn try_toggle_cell(&mut self, row: u32, column: u32) -> Result<(), LifeError> {
+        let idx = self.checked_index(row, column)?;
+        self.write_cell(idx, !self.cells[idx]);
+        Ok(())
     }
 
     /// Draws a blinker pattern centered on the specified cell
@@ -384,7 +1523,10 @@ impl Universe {
     ///
     /// * `row` - The row coordinate of the pattern center
     /// * `column` - The column coordinate of the pattern center
-    /// * `horizontal` - If true, creates horizontal blinker, otherwise vertical
+    /// * `orientation` - The rotation or reflection of the horizontal
+    ///   blinker, such as [`Transform::Rotate90`] for a vertical one
+    /// * `phase` - The number of generations to advance the blinker, which
+    ///   turns it every odd generation under the default rule
     ///
     /// Horizontal blinker:
     /// ```
@@ -397,56 +1539,96 @@ impl Universe {
     /// ☐
     /// ☐
     /// ```
-    pub fn draw_blinker(&mut self, row: u32, column: u32, horizontal: bool) {
-        // Draw center cell
-        let center_idx = self.get_index(row, column);
-        self.cells.set(center_idx, true);
-
-        if horizontal {
-            // Draw cells on either side
-            let left_idx = self.get_index(row, (column + self.width - 1) % self.width);
-            self.cells.set(left_idx, true);
-
-            let right_idx = self.get_index(row, (column + 1) % self.width);
-            self.cells.set(right_idx, true);
-        } else {
-            // Draw cells above and below
-            let top_idx = self.get_index((row + self.height - 1) % self.height, column);
-            self.cells.set(top_idx, true);
+    ///
+    /// # Panics
+    ///
+    /// Panics if `phase` is more than 1024 generations, see
+    /// [`Universe::try_draw_blinker`] for a fallible version.
+    pub fn draw_blinker(&mut self, row: u32, column: u32, orientation: Transform, phase: u32) {
+        self.try_draw_blinker(row, column, orientation, phase)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
 
-            let bottom_idx = self.get_index((row + 1) % self.height, column);
-            self.cells.set(bottom_idx, true);
-        }
+    /// Draws a blinker pattern centered on the specified cell, if `phase`
+    /// is at most 1024 generations
+    ///
+    /// See [`Universe::draw_blinker`] for the arguments.
+    pub fn try_draw_blinker(
+        &mut self,
+        row: u32,
+        column: u32,
+        orientation: Transform,
+        phase: u32,
+    ) -> Result<(), LifeError> {
+        self.place_offsets(
+            row,
+            column,
+            &patterns::BLINKER_HORIZONTAL,
+            orientation,
+            phase,
+            PasteMode::Or,
+        )
     }
 
     /// Draws a glider pattern centered on the specified cell
     ///
     /// A glider is a pattern that moves diagonally across the universe.
+    /// This one heads south-east before it is oriented, like the glider of
+    /// [`Universe::draw_pattern`]. The 3x3 square around the glider is
+    /// cleared.
     ///
     /// # Arguments
     ///
     /// * `row` - The row coordinate of the pattern center
     /// * `column` - The column coordinate of the pattern center
+    /// * `orientation` - The rotation or reflection of the glider
+    /// * `phase` - The number of generations to advance the glider, such
+    ///   as 0 to 3 for its four phases
     ///
     /// Initial state:
     /// ```
     ///   ☐
-    ///     ☐ ☐
-    ///   ☐   ☐
+    ///     ☐
+    /// ☐ ☐ ☐
     /// ```
-    pub fn draw_glider(&mut self, row: u32, column: u32) {
-        let top = ((row + self.height - 1) % self.height, column);
-        let center = (row, column);
-        let right = (row, (column + 1) % self.width);
-        let bot_left = (
-            (row + 1) % self.height,
-            (column + self.width - 1) % self.width,
-        );
-        let bot_right = ((row + 1) % self.height, (column + 1) % self.width);
-
-        let alive_cells = vec![top, center, right, bot_left, bot_right];
+    ///
+    /// # Panics
+    ///
+    /// Panics if the universe is smaller than 3x3 or `phase` is more than
+    /// 1024 generations, see [`Universe::try_draw_glider`] for a fallible
+    /// version.
+    pub fn draw_glider(&mut self, row: u32, column: u32, orientation: Transform, phase: u32) {
+        self.try_draw_glider(row, column, orientation, phase)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
 
-        self.draw_square_pattern(row, column, 3, &alive_cells);
+    /// Draws a glider pattern centered on the specified cell, if the
+    /// universe is large enough to hold it and `phase` is at most 1024
+    /// generations
+    ///
+    /// See [`Universe::draw_glider`] for the arguments.
+    pub fn try_draw_glider(
+        &mut self,
+        row: u32,
+        column: u32,
+        orientation: Transform,
+        phase: u32,
+    ) -> Result<(), LifeError> {
+        if self.width < 3 || self.height < 3 {
+            return Err(LifeError::PatternTooLarge {
+                size: 3,
+                width: self.width,
+                height: self.height,
+            });
+        }
+        self.place_offsets(
+            row,
+            column,
+            &patterns::GLIDER,
+            orientation,
+            phase,
+            PasteMode::Copy,
+        )
     }
 
     /// Draws a pulsar pattern centered on the specified cell
@@ -457,6 +1639,9 @@ impl Universe {
     ///
     /// * `row` - The row coordinate of the pattern center
     /// * `column` - The column coordinate of the pattern center
+    /// * `orientation` - The rotation or reflection of the pulsar
+    /// * `phase` - The number of generations to advance the pulsar, such
+    ///   as 0 to 2 for its three phases
     ///
     /// Initial state:
     /// ```
@@ -471,51 +1656,118 @@ impl Universe {
     ///       ☐      ☐  ☐      ☐
     ///          ☐☐☐     ☐☐☐
     /// ```
-    pub fn draw_pulsar(&mut self, row: u32, column: u32) {
-        // Horizontal blinkers
-        for center_col_offset in vec![self.width - 3, 3].iter() {
-            let center_col = (column + center_col_offset) % self.width;
+    ///
+    /// # Panics
+    ///
+    /// Panics if `phase` is more than 1024 generations, see
+    /// [`Universe::try_draw_pulsar`] for a fallible version.
+    pub fn draw_pulsar(&mut self, row: u32, column: u32, orientation: Transform, phase: u32) {
+        self.try_draw_pulsar(row, column, orientation, phase)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
 
-            // Top
-            let mut center_row = (row + self.height - 6) % self.height;
-            self.draw_blinker(center_row, center_col, true);
+    /// Draws a pulsar pattern centered on the specified cell, if `phase`
+    /// is at most 1024 generations
+    ///
+    /// See [`Universe::draw_pulsar`] for the arguments.
+    pub fn try_draw_pulsar(
+        &mut self,
+        row: u32,
+        column: u32,
+        orientation: Transform,
+        phase: u32,
+    ) -> Result<(), LifeError> {
+        self.place_offsets(
+            row,
+            column,
+            &patterns::pulsar(),
+            orientation,
+            phase,
+            PasteMode::Or,
+        )
+    }
 
-            // Middle top
-            center_row = (row + self.height - 1) % self.height;
-            self.draw_blinker(center_row, center_col, true);
+    /// Draws a pattern of the catalog centered on the specified cell
+    ///
+    /// Cells of the pattern that lie past an edge follow the universe's
+    /// topology and are skipped when that edge is not joined. Only the
+    /// pattern's live cells are drawn, see [`CatalogPattern::all`] for the
+    /// names of the patterns.
+    ///
+    /// The catalog's spaceships head south-east like its glider, or west
+    /// like the lightweight spaceship, so a glider heading north-west is
+    /// drawn rotated by 180 degrees, as with [`Universe::draw_glider`].
+    /// Later phases are reached by advancing the oriented pattern under the
+    /// universe's rule, keeping the center of its first phase on the
+    /// specified cell.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the pattern, such as `"gosper-glider-gun"`
+    /// * `row` - The row coordinate of the pattern center
+    /// * `column` - The column coordinate of the pattern center
+    /// * `orientation` - The rotation or reflection of the pattern as it
+    ///   is stored in the catalog
+    /// * `phase` - The number of generations to advance the pattern, such
+    ///   as 0 to 3 for the four phases of a glider
+    ///
+    /// # Returns
+    ///
+    /// An error if no pattern of the catalog has the name, or `phase` is
+    /// more than 1024 generations
+    pub fn draw_pattern(
+        &mut self,
+        name: &str,
+        row: u32,
+        column: u32,
+        orientation: Transform,
+        phase: u32,
+    ) -> Result<(), LifeError> {
+        let pattern = CatalogPattern::find(name)
+            .ok_or_else(|| LifeError::UnknownPattern(name.to_string()))?
+            .pattern();
+        let (width, height) = orientation.size(pattern.width(), pattern.height());
+        let top = row as i64 - (height / 2) as i64;
+        let left = column as i64 - (width / 2) as i64;
+        self.place(&pattern, top, left, orientation, phase, PasteMode::Or)
+    }
+}
 
-            // Middle bottom
-            center_row = (row + 1) % self.height;
-            self.draw_blinker(center_row, center_col, true);
+impl Default for Universe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-            // Bottom
-            center_row = (row + 6) % self.height;
-            self.draw_blinker(center_row, center_col, true);
+impl Simulation for Universe {
+    /// Advances the universe by calling [`Universe::tick`] once per generation
+    fn step(&mut self, generations: u64) {
+        for _ in 0..generations {
+            self.tick();
         }
+    }
 
-        // Vertical blinkers
-        for center_col_offset in vec![self.width - 6, self.width - 1, 1, 6].iter() {
-            let center_col = (column + center_col_offset) % self.width;
-
-            // Top
-            let mut center_row = (row + self.height - 3) % self.height;
-            self.draw_blinker(center_row, center_col, false);
+    fn generation(&self) -> u64 {
+        self.generation
+    }
 
-            // Bottom
-            center_row = (row + 3) % self.height;
-            self.draw_blinker(center_row, center_col, false);
-        }
+    fn population(&self) -> u64 {
+        self.cells.count_ones(..) as u64
     }
 }
 
 impl fmt::Display for Universe {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for line in self.cells.as_slice().chunks(self.width as usize) {
-            for &cell in line {
-                let symbol = if cell == false as usize { '◻' } else { '◼' };
+        for row in 0..self.height {
+            for column in 0..self.width {
+                let symbol = match self.state_at(self.get_index(row, column)) {
+                    0 => '◻',
+                    1 => '◼',
+                    _ => '▣',
+                };
                 write!(f, "{}", symbol)?;
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
 
         Ok(())
@@ -535,6 +1787,16 @@ macro_rules! log {
 mod tests {
     use super::*;
 
+    /// Returns the `(row, column)` of every live cell in row-major order
+    fn live_cells(universe: &Universe) -> Vec<(u32, u32)> {
+        let width = universe.width();
+        universe
+            .get_cells()
+            .ones()
+            .map(|idx| (idx as u32 / width, idx as u32 % width))
+            .collect()
+    }
+
     #[test]
     fn test_universe_creation() {
         let universe = Universe::new();
@@ -567,7 +1829,7 @@ mod tests {
     #[test]
     fn test_draw_blinker() {
         let mut universe = Universe::new_empty(10, 10);
-        universe.draw_blinker(5, 5, true); // Horizontal blinker
+        universe.draw_blinker(5, 5, Transform::Identity, 0); // Horizontal blinker
 
         // Check that the blinker pattern is drawn correctly
         assert!(universe.is_cell_alive(5, 4)); // Left cell
@@ -578,39 +1840,39 @@ mod tests {
     #[test]
     fn test_draw_glider() {
         let mut universe = Universe::new_empty(10, 10);
-        universe.draw_glider(5, 5);
+        universe.draw_glider(5, 5, Transform::Identity, 0);
 
         // Check that the glider pattern is drawn correctly
         assert!(universe.is_cell_alive(4, 5)); // Top cell
-        assert!(universe.is_cell_alive(5, 5)); // Center cell
         assert!(universe.is_cell_alive(5, 6)); // Right cell
         assert!(universe.is_cell_alive(6, 4)); // Bottom left cell
+        assert!(universe.is_cell_alive(6, 5)); // Bottom center cell
         assert!(universe.is_cell_alive(6, 6)); // Bottom right cell
 
         // Check that other cells around the pattern are dead
         assert!(!universe.is_cell_alive(4, 4)); // Top left
         assert!(!universe.is_cell_alive(4, 6)); // Top right
         assert!(!universe.is_cell_alive(5, 4)); // Center left
-        assert!(!universe.is_cell_alive(6, 5)); // Bottom center
+        assert!(!universe.is_cell_alive(5, 5)); // Center
     }
 
     #[test]
     fn test_draw_glider_wrapping() {
         let mut universe = Universe::new_empty(5, 5);
-        universe.draw_glider(0, 0); // Test at edge with wrapping
+        universe.draw_glider(0, 0, Transform::Identity, 0); // Test at edge with wrapping
 
         // Check that the glider pattern wraps correctly at the edges
         assert!(universe.is_cell_alive(4, 0)); // Top cell (wrapped)
-        assert!(universe.is_cell_alive(0, 0)); // Center cell
         assert!(universe.is_cell_alive(0, 1)); // Right cell
         assert!(universe.is_cell_alive(1, 4)); // Bottom left cell (wrapped)
+        assert!(universe.is_cell_alive(1, 0)); // Bottom center cell
         assert!(universe.is_cell_alive(1, 1)); // Bottom right cell
     }
 
     #[test]
     fn test_glider_evolution() {
         let mut universe = Universe::new_empty(10, 10);
-        universe.draw_glider(5, 5);
+        universe.draw_glider(5, 5, Transform::Identity, 0);
 
         // Count initial alive cells
         let initial_alive = universe.get_cells().count_ones(..);
@@ -628,10 +1890,37 @@ mod tests {
         assert!(!universe.is_cell_alive(5, 5)); // Center should no longer be alive
     }
 
+    #[test]
+    fn test_draw_in_orientation_and_phase() {
+        // A blinker turns every generation
+        let mut turned = Universe::new_empty(10, 10);
+        turned.draw_blinker(5, 5, Transform::Identity, 1);
+        let mut vertical = Universe::new_empty(10, 10);
+        vertical.draw_blinker(5, 5, Transform::Rotate90, 0);
+        assert_eq!(live_cells(&turned), live_cells(&vertical));
+
+        let mut universe = Universe::new_empty(30, 30);
+        universe.draw_glider(5, 5, Transform::FlipVertical, 0);
+        universe.draw_pulsar(20, 20, Transform::Rotate90, 0);
+        let mut placed = Universe::new_empty(30, 30);
+        placed.draw_glider(5, 5, Transform::FlipVertical, 2);
+        placed.draw_pulsar(20, 20, Transform::Rotate90, 2);
+        universe.step(2);
+        assert_eq!(live_cells(&placed), live_cells(&universe));
+
+        assert_eq!(
+            placed.try_draw_pulsar(0, 0, Transform::Identity, 2000),
+            Err(LifeError::PhaseTooLarge {
+                phase: 2000,
+                max: 1024,
+            })
+        );
+    }
+
     #[test]
     fn test_draw_pulsar() {
         let mut universe = Universe::new_empty(20, 20);
-        universe.draw_pulsar(10, 10);
+        universe.draw_pulsar(10, 10, Transform::Identity, 0);
 
         // Check horizontal blinkers (top and bottom sections)
         // Top horizontal blinker - spans columns 7-8 and 12-13
@@ -674,19 +1963,606 @@ mod tests {
     #[test]
     fn test_pulsar_oscillation() {
         let mut universe = Universe::new_empty(20, 20);
-        universe.draw_pulsar(10, 10);
+        universe.draw_pulsar(10, 10, Transform::Identity, 0);
 
         // Count initial alive cells
         let initial_alive = universe.get_cells().count_ones(..);
         assert_eq!(initial_alive, 48); // Pulsar has 48 cells in its pattern
 
-        // Tick the universe multiple times to test oscillation
-        for _ in 0..3 {
+        // The pulsar should return to its original state after 3 ticks
+        assert_eq!(
+            universe.find_cycle(10),
+            Some(Cycle {
+                start: 0,
+                period: 3,
+                kind: CycleKind::Oscillator
+            })
+        );
+    }
+
+    #[test]
+    fn test_with_rule() {
+        // Seeds (B2/S): no cell survives and cells with two neighbors are born
+        let seeds = Rule::parse("B2/S").unwrap();
+        let mut universe = Universe::with_rule(10, 10, seeds);
+        universe.set_cells(&[(5, 4), (5, 5)]);
+
+        universe.tick();
+
+        assert!(!universe.is_cell_alive(5, 4));
+        assert!(!universe.is_cell_alive(5, 5));
+        assert!(universe.is_cell_alive(4, 4));
+        assert!(universe.is_cell_alive(4, 5));
+        assert!(universe.is_cell_alive(6, 4));
+        assert!(universe.is_cell_alive(6, 5));
+        assert_eq!(universe.get_cells().count_ones(..), 4);
+    }
+
+    #[test]
+    fn test_set_rule() {
+        let mut universe = Universe::new_empty(10, 10);
+        assert_eq!(universe.rulestring(), "B3/S23");
+
+        universe.set_rule("23/36").unwrap();
+        assert_eq!(universe.rule(), Rule::new(&[3, 6], &[2, 3]));
+        assert_eq!(universe.rulestring(), "B36/S23");
+
+        assert!(universe.set_rule("B3/S2x").is_err());
+        assert_eq!(universe.rulestring(), "B36/S23");
+    }
+
+    #[test]
+    fn test_plane_neighbors_do_not_wrap() {
+        // A blinker on the edge of a torus sees its wrapped neighbors,
+        // while on a plane the cells past the edge are dead
+        let mut torus = Universe::new_empty(5, 5);
+        torus.draw_blinker(0, 2, Transform::Rotate90, 0);
+        torus.tick();
+        assert_eq!(torus.get_cells().count_ones(..), 3);
+
+        let mut plane = Universe::new_empty(5, 5);
+        plane.set_topology(Topology::Plane);
+        plane.draw_blinker(0, 2, Transform::Rotate90, 0);
+        assert!(!plane.is_cell_alive(4, 2));
+        assert_eq!(plane.get_cells().count_ones(..), 2);
+        plane.tick();
+        assert_eq!(plane.get_cells().count_ones(..), 0);
+    }
+
+    #[test]
+    fn test_draw_glider_on_plane_is_clipped() {
+        let mut universe = Universe::new_empty(5, 5);
+        universe.set_topology(Topology::Plane);
+        universe.draw_glider(0, 0, Transform::Identity, 0);
+
+        assert!(universe.is_cell_alive(0, 1)); // Right cell
+        assert!(universe.is_cell_alive(1, 0)); // Bottom center cell
+        assert!(universe.is_cell_alive(1, 1)); // Bottom right cell
+        assert_eq!(universe.get_cells().count_ones(..), 3);
+    }
+
+    #[test]
+    fn test_glider_stops_at_plane_edge() {
+        let mut universe = Universe::new_empty(10, 10);
+        universe.set_topology(Topology::Plane);
+        universe.draw_glider(2, 2, Transform::FlipVertical, 0);
+
+        for _ in 0..40 {
             universe.tick();
         }
 
-        // After 3 ticks, the pulsar should return to its original state
-        let after_3_ticks_alive = universe.get_cells().count_ones(..);
-        assert_eq!(after_3_ticks_alive, 48);
+        // The glider crashes into the top edge and settles into a block
+        let block = [(0, 3), (0, 4), (1, 3), (1, 4)];
+        assert_eq!(universe.get_cells().count_ones(..), 4);
+        for (row, col) in block {
+            assert!(universe.is_cell_alive(row, col));
+        }
+    }
+
+    #[test]
+    fn test_draw_glider_on_klein_bottle() {
+        let mut universe = Universe::new_empty(5, 5);
+        universe.set_topology(Topology::KleinBottle);
+        universe.draw_glider(0, 1, Transform::Identity, 0);
+
+        // The top cell wraps to the bottom row with its column mirrored
+        assert!(universe.is_cell_alive(4, 3));
+        assert!(universe.is_cell_alive(0, 2));
+        assert!(universe.is_cell_alive(1, 0));
+        assert!(universe.is_cell_alive(1, 1));
+        assert!(universe.is_cell_alive(1, 2));
+        assert_eq!(universe.get_cells().count_ones(..), 5);
+    }
+
+    #[test]
+    fn test_try_cell_methods_check_bounds() {
+        let mut universe = Universe::new_empty(8, 4);
+        let out_of_bounds = Err(LifeError::CellOutOfBounds {
+            row: 4,
+            column: 0,
+            width: 8,
+            height: 4,
+        });
+
+        assert_eq!(universe.try_set_cell(4, 0, true), out_of_bounds);
+        assert_eq!(
+            universe.try_is_cell_alive(4, 0),
+            out_of_bounds.map(|_| false)
+        );
+        assert!(universe.try_toggle_cell(0, 8).is_err());
+        assert_eq!(universe.get_cells().count_ones(..), 0);
+
+        universe.try_toggle_cell(3, 7).unwrap();
+        assert_eq!(universe.try_is_cell_alive(3, 7), Ok(true));
+    }
+
+    #[test]
+    fn test_try_set_cells_is_atomic() {
+        let mut universe = Universe::new_empty(8, 8);
+
+        assert!(universe.try_set_cells(&[(1, 1), (2, 2), (8, 0)]).is_err());
+        assert_eq!(universe.get_cells().count_ones(..), 0);
+
+        universe.try_set_cells(&[(1, 1), (2, 2)]).unwrap();
+        assert_eq!(universe.get_cells().count_ones(..), 2);
+    }
+
+    #[test]
+    fn test_try_draw_glider_in_small_universe() {
+        let mut universe = Universe::new_empty(2, 5);
+        assert_eq!(
+            universe.try_draw_glider(1, 1, Transform::Identity, 0),
+            Err(LifeError::PatternTooLarge {
+                size: 3,
+                width: 2,
+                height: 5
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cell (0, 10) lies outside of the 10x10 universe")]
+    fn test_set_cell_out_of_bounds_panics() {
+        let mut universe = Universe::new_empty(10, 10);
+        universe.set_cell(0, 10, true);
+    }
+
+    #[test]
+    fn test_generations_rule() {
+        let mut universe = Universe::new_empty(4, 4);
+        universe.set_rule("B2/S/C3").unwrap();
+        universe.set_cells(&[(1, 1), (1, 2)]);
+
+        universe.tick();
+
+        // Both live cells die, becoming refractory, while the cells above and
+        // below them are born
+        assert_eq!(universe.cell_state(1, 1), 2);
+        assert_eq!(universe.cell_state(1, 2), 2);
+        assert!(universe.is_cell_alive(0, 1));
+        assert!(universe.is_cell_alive(2, 2));
+        assert_eq!(universe.render(), "◻◼◼◻\n◻▣▣◻\n◻◼◼◻\n◻◻◻◻\n");
+
+        universe.tick();
+        assert_eq!(universe.cell_state(1, 1), 0);
+        assert_eq!(universe.cell_state(2, 1), 2);
+    }
+
+    #[test]
+    fn test_set_cell_state() {
+        let mut universe = Universe::with_rule(4, 4, Rule::generations(&[2], &[], 4));
+        universe.set_cell_state(0, 0, 3);
+        universe.set_cell_state(0, 1, 1);
+        assert_eq!(&universe.cell_states()[..3], &[3, 1, 0]);
+
+        assert_eq!(
+            universe.try_set_cell_state(0, 0, 4),
+            Err(LifeError::InvalidState {
+                state: 4,
+                states: 4
+            })
+        );
+
+        // Setting a dying cell alive ends its refractory state
+        universe.set_cell(0, 0, true);
+        assert_eq!(universe.cell_state(0, 0), 1);
+    }
+
+    #[test]
+    fn test_rle_round_trip() {
+        let mut universe = Universe::with_rule(20, 20, Rule::parse("B36/S23").unwrap());
+        universe.draw_pulsar(10, 10, Transform::Identity, 0);
+        universe.draw_glider(1, 1, Transform::Identity, 0);
+
+        let round_trip = Universe::from_rle(&universe.to_rle()).unwrap();
+        assert_eq!(round_trip.width(), 20);
+        assert_eq!(round_trip.height(), 20);
+        assert_eq!(round_trip.rule(), universe.rule());
+        assert_eq!(round_trip.get_cells(), universe.get_cells());
+    }
+
+    #[test]
+    fn test_plaintext_round_trip() {
+        let glider = Universe::from_plaintext("!Name: Glider\n.O.\n..O\nOOO\n").unwrap();
+        assert_eq!(glider.width(), 3);
+        assert_eq!(glider.height(), 3);
+        assert_eq!(glider.to_plaintext(), ".O.\n..O\nOOO\n");
+    }
+
+    #[test]
+    fn test_load_plaintext_at_offset() {
+        let mut universe = Universe::new_empty(10, 10);
+        universe.load_plaintext("OO\nOO", 4, 6).unwrap();
+
+        for (row, col) in [(4, 6), (4, 7), (5, 6), (5, 7)] {
+            assert!(universe.is_cell_alive(row, col));
+        }
+        assert_eq!(universe.get_cells().count_ones(..), 4);
+    }
+
+    #[test]
+    fn test_load_life106_wraps_negative_coordinates() {
+        let mut universe = Universe::new_empty(10, 10);
+        universe
+            .load_life106("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n", 0, 0)
+            .unwrap();
+
+        assert!(universe.is_cell_alive(9, 0));
+        assert!(universe.is_cell_alive(0, 1));
+        assert!(universe.is_cell_alive(1, 9));
+        assert!(universe.is_cell_alive(1, 0));
+        assert!(universe.is_cell_alive(1, 1));
+
+        let mut copy = Universe::new_empty(10, 10);
+        copy.load_life106(&universe.to_life106(), 0, 0).unwrap();
+        assert_eq!(copy.get_cells(), universe.get_cells());
+
+        // Cells whose coordinates do not fit are skipped
+        let mut universe = Universe::new_empty(10, 10);
+        universe
+            .load_life106("#Life 1.06\n9223372036854775807 0\n1 1\n", 2, 2)
+            .unwrap();
+        assert_eq!(live_cells(&universe), vec![(3, 3)]);
+    }
+
+    #[test]
+    fn test_load_life105_sets_rule() {
+        let mut universe = Universe::new_empty(10, 10);
+        universe
+            .load_life105("#Life 1.05\n#R 23/36\n#P 1 2\n***\n", 3, 3)
+            .unwrap();
+
+        assert_eq!(universe.rulestring(), "B36/S23");
+        assert!(universe.is_cell_alive(5, 4));
+        assert!(universe.is_cell_alive(5, 5));
+        assert!(universe.is_cell_alive(5, 6));
+        assert_eq!(universe.get_cells().count_ones(..), 3);
+    }
+
+    #[test]
+    fn test_from_life106_crops_to_live_cells() {
+        let universe = Universe::from_life106("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n").unwrap();
+
+        assert_eq!((universe.width(), universe.height()), (3, 3));
+        assert_eq!(universe.to_plaintext(), ".O.\n..O\nOOO\n");
+        assert_eq!(universe.bounding_box(), Some((0, 0, 2, 2)));
+        assert_eq!(Universe::new_empty(4, 4).bounding_box(), None);
+
+        assert_eq!(
+            Universe::from_life106("#Life 1.06\n9223372036854775807 0\n-9223372036854775808 0\n")
+                .err(),
+            Some(PatternParseError::PatternTooLarge {
+                width: u64::MAX,
+                height: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_life105_uses_rule() {
+        let universe = Universe::from_life105("#Life 1.05\n#R 23/36\n#P -5 9\n*.*\n").unwrap();
+
+        assert_eq!(universe.rulestring(), "B36/S23");
+        assert_eq!((universe.width(), universe.height()), (3, 1));
+        assert_eq!(universe.population(), 2);
+    }
+
+    #[test]
+    fn test_pattern_format_detection() {
+        let glider = Universe::from_plaintext(".O.\n..O\nOOO\n").unwrap();
+        for format in [
+            PatternFormat::Rle,
+            PatternFormat::Plaintext,
+            PatternFormat::Life105,
+            PatternFormat::Life106,
+            PatternFormat::Macrocell,
+        ] {
+            let text = glider.to_pattern(format);
+            assert_eq!(PatternFormat::detect(&text), format);
+            let restored = Universe::from_pattern(&text).unwrap();
+            assert_eq!(
+                restored.to_plaintext(),
+                glider.to_plaintext(),
+                "{:?}",
+                format
+            );
+        }
+
+        assert_eq!(
+            PatternFormat::from_extension("RLE"),
+            Some(PatternFormat::Rle)
+        );
+        assert_eq!(PatternFormat::from_extension("png"), None);
+    }
+
+    #[test]
+    fn test_resize_keeps_cells() {
+        let mut universe = Universe::with_rule(6, 4, Rule::parse("B2/S/C3").unwrap());
+        universe.set_cells(&[(0, 0), (3, 5)]);
+        universe.set_cell_state(1, 2, 2);
+        universe.set_topology(Topology::Plane);
+
+        universe.resize(10, 8, Anchor::Center);
+        assert_eq!((universe.width(), universe.height()), (10, 8));
+        assert_eq!(universe.topology(), Topology::Plane);
+        assert!(universe.is_cell_alive(2, 2));
+        assert!(universe.is_cell_alive(5, 7));
+        assert_eq!(universe.cell_state(3, 4), 2);
+        assert_eq!(universe.population(), 2);
+
+        // Shrinking back to the center restores the original cells
+        universe.resize(6, 4, Anchor::Center);
+        assert!(universe.is_cell_alive(0, 0));
+        assert!(universe.is_cell_alive(3, 5));
+        assert_eq!(universe.cell_state(1, 2), 2);
+
+        universe.resize(3, 3, Anchor::BottomRight);
+        assert_eq!(universe.cell_states(), vec![0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_too_large_universes_are_rejected() {
+        let too_large = LifeError::UniverseTooLarge {
+            width: 100_000,
+            height: 100_000,
+        };
+        assert_eq!(
+            Universe::try_new_empty(100_000, 100_000).err(),
+            Some(too_large.clone())
+        );
+        assert!(Universe::try_new_empty(u32::MAX, u32::MAX).is_err());
+
+        let mut universe = Universe::new_empty(4, 4);
+        universe.set_cell(1, 1, true);
+        assert_eq!(
+            universe.try_resize(100_000, 100_000, Anchor::Center),
+            Err(too_large)
+        );
+        assert_eq!((universe.width(), universe.height()), (4, 4));
+        assert!(universe.is_cell_alive(1, 1));
+    }
+
+    #[test]
+    fn test_transform_swaps_dimensions() {
+        let mut universe = Universe::with_rule(3, 2, Rule::parse("B2/S/C3").unwrap());
+        universe.set_cell(0, 0, true);
+        universe.set_cell_state(1, 2, 2);
+
+        let rotated = universe.transformed(Transform::Rotate90);
+        assert_eq!((rotated.width(), rotated.height()), (2, 3));
+        assert!(rotated.is_cell_alive(0, 1));
+        assert_eq!(rotated.cell_state(2, 0), 2);
+
+        let flipped = universe.transformed(Transform::FlipHorizontal);
+        assert_eq!(flipped.cell_states(), vec![0, 0, 1, 2, 0, 0]);
+
+        // Four quarter turns bring the universe back
+        let mut turned = universe.clone();
+        for _ in 0..4 {
+            turned.transform(Transform::Rotate90);
+        }
+        assert_eq!(turned.cell_states(), universe.cell_states());
+        assert_eq!(
+            universe.transformed(Transform::Rotate270).cell_states(),
+            rotated.transformed(Transform::Rotate180).cell_states()
+        );
+    }
+
+    #[test]
+    fn test_transform_region() {
+        let mut universe = Universe::new_empty(6, 6);
+        universe.set_cells(&[(1, 1), (1, 2), (2, 1), (5, 5)]);
+        universe
+            .transform_region(1, 1, 3, 3, Transform::FlipHorizontal)
+            .unwrap();
+        assert_eq!(live_cells(&universe), vec![(1, 2), (1, 3), (2, 3), (5, 5)]);
+
+        // A 3 by 1 region turned a quarter covers 1 by 3 cells
+        universe.clear();
+        universe.set_cells(&[(4, 3), (4, 4), (4, 5)]);
+        assert!(universe
+            .transform_region(4, 3, 3, 1, Transform::Rotate90)
+            .is_err());
+        assert_eq!(universe.population(), 3);
+        universe
+            .transform_region(4, 3, 3, 1, Transform::Rotate180)
+            .unwrap();
+        universe.translate(-3, 0, false);
+        universe
+            .transform_region(1, 3, 3, 1, Transform::Transpose)
+            .unwrap();
+        assert_eq!(live_cells(&universe), vec![(1, 3), (2, 3), (3, 3)]);
+    }
+
+    #[test]
+    fn test_translate() {
+        let mut universe = Universe::new_empty(4, 4);
+        universe.set_cells(&[(0, 0), (3, 3)]);
+
+        let wrapped = universe.translated(-1, 2, true);
+        assert_eq!(live_cells(&wrapped), vec![(2, 1), (3, 2)]);
+
+        universe.translate(-1, -1, false);
+        assert_eq!(live_cells(&universe), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn test_cut_and_paste() {
+        let mut universe = Universe::new_empty(8, 8);
+        universe.draw_glider(2, 2, Transform::Identity, 0);
+        let glider = universe.cut_region(1, 1, 3, 3);
+        assert_eq!(universe.population(), 0);
+        assert_eq!(glider.population(), 5);
+
+        // A region too large to store is rejected without killing any cell
+        universe.draw_glider(2, 2, Transform::Identity, 0);
+        let too_large = Err(LifeError::UniverseTooLarge {
+            width: 100000,
+            height: 100000,
+        });
+        assert_eq!(universe.try_copy_region(0, 0, 100000, 100000), too_large);
+        assert_eq!(universe.try_cut_region(0, 0, 100000, 100000), too_large);
+        assert_eq!(universe.population(), 5);
+        universe.clear();
+
+        // Pasting across the corner of a torus wraps to the other edges
+        universe
+            .paste(&glider, 7, 7, Transform::Identity, 0, PasteMode::Copy)
+            .unwrap();
+        let mut expected = Universe::new_empty(8, 8);
+        expected.draw_glider(0, 0, Transform::Identity, 0);
+        assert_eq!(live_cells(&universe), live_cells(&expected));
+
+        // Under the glider, only the cells alive in both remain
+        universe
+            .paste(&glider, 7, 6, Transform::Identity, 0, PasteMode::And)
+            .unwrap();
+        assert_eq!(live_cells(&universe), vec![(0, 1), (1, 0), (1, 1), (1, 7)]);
+
+        universe
+            .paste(&glider, 7, 6, Transform::Identity, 0, PasteMode::Xor)
+            .unwrap();
+        assert_eq!(universe.population(), 5);
+
+        // The dead cells of the glider erase cells only when copied
+        universe.set_topology(Topology::Plane);
+        universe.clear();
+        universe.set_cell(0, 0, true);
+        universe
+            .paste(&glider, 0, 0, Transform::Identity, 0, PasteMode::LiveOnly)
+            .unwrap();
+        assert_eq!(universe.population(), 6);
+        universe
+            .paste(&glider, 0, 0, Transform::Identity, 0, PasteMode::Copy)
+            .unwrap();
+        assert_eq!(universe.population(), 5);
+
+        // Cells past the edges of a plane are skipped
+        universe
+            .paste(&glider, 6, 6, Transform::Identity, 0, PasteMode::Or)
+            .unwrap();
+        assert_eq!(universe.population(), 6);
+        assert_eq!(universe.copy_region(6, 6, 3, 3).population(), 1);
+    }
+
+    #[test]
+    fn test_draw_pattern() {
+        let mut universe = Universe::new_empty(10, 10);
+        universe
+            .draw_pattern("glider", 5, 5, Transform::Identity, 0)
+            .unwrap();
+        assert_eq!(
+            live_cells(&universe),
+            vec![(4, 5), (5, 6), (6, 4), (6, 5), (6, 6)]
+        );
+
+        // Names ignore case, and the pulsar looks the same when rotated
+        universe.clear();
+        universe
+            .draw_pattern("Pulsar", 6, 6, Transform::Rotate90, 0)
+            .unwrap();
+        let mut expected = Universe::new_empty(10, 10);
+        expected.draw_pulsar(6, 6, Transform::Identity, 0);
+        assert_eq!(live_cells(&universe), live_cells(&expected));
+
+        assert_eq!(
+            universe.draw_pattern("unknown", 0, 0, Transform::Identity, 0),
+            Err(LifeError::UnknownPattern("unknown".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_place_in_orientation_and_phase() {
+        // A glider turned around heads north-west
+        let mut universe = Universe::new_empty(12, 12);
+        universe
+            .draw_pattern("glider", 5, 5, Transform::Rotate180, 0)
+            .unwrap();
+        assert_eq!(
+            live_cells(&universe),
+            vec![(4, 4), (4, 5), (4, 6), (5, 4), (6, 5)]
+        );
+
+        // Later phases are where the cells would be after stepping
+        for phase in 1..=4 {
+            let mut stepped = universe.clone();
+            stepped.step(phase as u64);
+            let mut placed = Universe::new_empty(12, 12);
+            placed
+                .draw_pattern("glider", 5, 5, Transform::Rotate180, phase)
+                .unwrap();
+            assert_eq!(live_cells(&placed), live_cells(&stepped), "{}", phase);
+        }
+
+        let glider = universe.copy_region(4, 4, 3, 3);
+        let mut pasted = Universe::new_empty(12, 12);
+        pasted
+            .paste(&glider, 2, 3, Transform::Transpose, 2, PasteMode::Copy)
+            .unwrap();
+        let mut expected = Universe::new_empty(12, 12);
+        expected
+            .paste(
+                &glider.transformed(Transform::Transpose),
+                2,
+                3,
+                Transform::Identity,
+                0,
+                PasteMode::Copy,
+            )
+            .unwrap();
+        expected.step(2);
+        assert_eq!(live_cells(&pasted), live_cells(&expected));
+
+        assert_eq!(
+            pasted.paste(&glider, 0, 0, Transform::Identity, u32::MAX, PasteMode::Or),
+            Err(LifeError::PhaseTooLarge {
+                phase: u32::MAX,
+                max: 1024,
+            })
+        );
+        assert_eq!(
+            pasted.draw_pattern("glider", 0, 0, Transform::Identity, 1025),
+            Err(LifeError::PhaseTooLarge {
+                phase: 1025,
+                max: 1024,
+            })
+        );
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let mut universe = Universe::new_empty(10, 8);
+        universe.set_cells(&[(2, 7), (3, 1), (6, 4)]);
+        assert_eq!(universe.bounding_box(), Some((2, 1, 6, 7)));
+    }
+
+    #[test]
+    fn test_simulation_step() {
+        let mut universe = Universe::new_empty(10, 10);
+        universe.draw_glider(5, 5, Transform::Identity, 0);
+
+        universe.step(4);
+        assert_eq!(universe.generation(), 4);
+        assert_eq!(universe.population(), 5);
     }
 }
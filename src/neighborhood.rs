@@ -0,0 +1,112 @@
+//! The cells that count as the neighbors of a cell
+
+use crate::rule::NEIGHBOR_OFFSETS;
+
+/// The largest range of an extended neighborhood
+pub(crate) const MAX_RANGE: u32 = 500;
+
+/// The cells around a cell that count as its neighbors
+///
+/// Range 1 neighborhoods are written as a suffix of a B/S rulestring, e.g.
+/// `B2/S34H`, while larger ranges use the Larger than Life notation, e.g.
+/// `R5,C0,M1,S34..58,B34..45,NM`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Neighborhood {
+    /// The cells at most `range` rows and columns away, the 8 surrounding
+    /// cells at range 1
+    Moore(u32),
+    /// The cells at most `range` steps away along rows and columns, the 4
+    /// orthogonally adjacent cells at range 1 (`V`)
+    VonNeumann(u32),
+    /// The 6 neighbors of a hexagonal grid emulated on the square grid,
+    /// which are the Moore neighbors except for the NE and SW cells (`H`)
+    Hexagonal,
+}
+
+impl Neighborhood {
+    /// Returns how many rows and columns away the furthest neighbor is
+    pub fn range(&self) -> u32 {
+        match *self {
+            Neighborhood::Moore(range) | Neighborhood::VonNeumann(range) => range,
+            Neighborhood::Hexagonal => 1,
+        }
+    }
+
+    /// Returns the number of neighbors of a cell, not counting the cell itself
+    pub fn size(&self) -> u32 {
+        match *self {
+            Neighborhood::Moore(range) => (2 * range + 1).pow(2) - 1,
+            Neighborhood::VonNeumann(range) => 2 * range * (range + 1),
+            Neighborhood::Hexagonal => 6,
+        }
+    }
+
+    /// Returns whether the cell at an offset from a cell is one of its neighbors
+    ///
+    /// # Arguments
+    ///
+    /// * `delta_row` - The row offset of the other cell
+    /// * `delta_col` - The column offset of the other cell
+    pub fn contains(&self, delta_row: i64, delta_col: i64) -> bool {
+        if (delta_row, delta_col) == (0, 0) {
+            return false;
+        }
+
+        let range = self.range() as i64;
+        match self {
+            Neighborhood::Moore(_) => delta_row.abs() <= range && delta_col.abs() <= range,
+            Neighborhood::VonNeumann(_) => delta_row.abs() + delta_col.abs() <= range,
+            Neighborhood::Hexagonal => {
+                delta_row.abs() <= 1 && delta_col.abs() <= 1 && delta_row != -delta_col
+            }
+        }
+    }
+
+    /// Returns the neighbors of a range 1 neighborhood as bits in the order
+    /// of [`Rule::next_state_for_neighbors`](crate::Rule::next_state_for_neighbors)
+    pub(crate) fn mask(&self) -> u8 {
+        NEIGHBOR_OFFSETS
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, (delta_row, delta_col))| self.contains(delta_row, delta_col))
+            .fold(0, |mask, (bit, _)| mask | (1 << bit))
+    }
+}
+
+impl Default for Neighborhood {
+    fn default() -> Self {
+        Neighborhood::Moore(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_matches_contains() {
+        for neighborhood in [
+            Neighborhood::Moore(1),
+            Neighborhood::Moore(5),
+            Neighborhood::VonNeumann(1),
+            Neighborhood::VonNeumann(4),
+            Neighborhood::Hexagonal,
+        ] {
+            let range = neighborhood.range() as i64;
+            let count = (-range..=range)
+                .flat_map(|row| (-range..=range).map(move |col| (row, col)))
+                .filter(|&(row, col)| neighborhood.contains(row, col))
+                .count();
+            assert_eq!(count as u32, neighborhood.size(), "{:?}", neighborhood);
+        }
+    }
+
+    #[test]
+    fn test_range_one_masks() {
+        assert_eq!(Neighborhood::Moore(1).mask(), 0xff);
+        // N, E, S and W
+        assert_eq!(Neighborhood::VonNeumann(1).mask(), 0x55);
+        // Everything but NE and SW
+        assert_eq!(Neighborhood::Hexagonal.mask(), 0xdd);
+    }
+}
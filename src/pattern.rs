@@ -0,0 +1,263 @@
+//! Rectangular patterns copied out of a universe and pasted back into one
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{LifeError, Transform, Universe};
+
+/// The most generations a pattern is advanced before it is placed in a
+/// universe, see [`Universe::paste`](crate::Universe::paste)
+pub(crate) const MAX_PHASE: u32 = 1024;
+
+/// A rectangle of cell states that is not part of any universe
+///
+/// Patterns are copied or cut from a universe with
+/// [`Universe::copy_region`](crate::Universe::copy_region) and
+/// [`Universe::cut_region`](crate::Universe::cut_region), and stamped into
+/// one with [`Universe::paste`](crate::Universe::paste). They keep the
+/// refractory states of dying cells, see
+/// [`Universe::cell_state`](crate::Universe::cell_state).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    width: u32,
+    height: u32,
+    /// The state of every cell, row by row
+    states: Vec<u8>,
+}
+
+/// How the cells of a pattern are combined with the cells they are pasted
+/// over
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PasteMode {
+    /// Sets the pattern's live cells alive and leaves every other cell as
+    /// it was
+    #[default]
+    Or,
+    /// Keeps a cell alive only if it is alive in both the universe and the
+    /// pattern, and kills every other cell under the pattern
+    And,
+    /// Sets a cell alive if it is alive in exactly one of the universe and
+    /// the pattern, and kills every other cell under the pattern
+    Xor,
+    /// Replaces every cell under the pattern with the pattern's cell
+    Copy,
+    /// Replaces only the cells under the pattern's cells that are not
+    /// dead, so dead cells of the pattern are transparent
+    LiveOnly,
+}
+
+impl PasteMode {
+    /// Returns the state of a cell after a pattern is pasted over it, or
+    /// `None` if the cell is left as it was
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The state of the pattern's cell
+    /// * `universe` - The state of the universe's cell
+    pub(crate) fn combine(self, pattern: u8, universe: u8) -> Option<u8> {
+        let (pattern_alive, universe_alive) = (pattern == 1, universe == 1);
+        match self {
+            PasteMode::Or => pattern_alive.then_some(1),
+            PasteMode::And => Some((pattern_alive && universe_alive) as u8),
+            PasteMode::Xor => Some((pattern_alive != universe_alive) as u8),
+            PasteMode::Copy => Some(pattern),
+            PasteMode::LiveOnly => (pattern != 0).then_some(pattern),
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Pattern {
+    /// Creates a new pattern of dead cells
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the pattern
+    /// * `height` - The height of the pattern
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern would have more than 2^30 cells, see
+    /// [`Pattern::try_new`] for a fallible version.
+    pub fn new(width: u32, height: u32) -> Pattern {
+        Pattern::try_new(width, height).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new pattern of dead cells, if it is not too large
+    ///
+    /// See [`Pattern::new`] for the arguments.
+    ///
+    /// # Returns
+    ///
+    /// An error if the pattern would have more than 2^30 cells
+    pub fn try_new(width: u32, height: u32) -> Result<Pattern, LifeError> {
+        Ok(Pattern {
+            width,
+            height,
+            states: vec![0; Universe::cell_count(width, height)?],
+        })
+    }
+
+    /// Returns the width of the pattern
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the pattern
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the state of a cell of the pattern: 0 when dead, 1 when
+    /// alive and 2 or more when dying
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell lies outside of the pattern.
+    pub fn cell_state(&self, row: u32, column: u32) -> u8 {
+        self.states[self.index(row, column)]
+    }
+
+    /// Sets the state of a cell of the pattern
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell lies outside of the pattern.
+    pub fn set_cell_state(&mut self, row: u32, column: u32, state: u8) {
+        let idx = self.index(row, column);
+        self.states[idx] = state;
+    }
+
+    /// Returns the number of live cells in the pattern
+    pub fn population(&self) -> u32 {
+        self.states.iter().filter(|&&state| state == 1).count() as u32
+    }
+
+    /// Returns a copy of the pattern rotated or reflected, with its width
+    /// and height swapped by quarter turns and transposes
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The rotation or reflection to apply
+    pub fn transformed(&self, transform: Transform) -> Pattern {
+        let (width, height) = transform.size(self.width, self.height);
+        let mut transformed = Pattern::new(width, height);
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let (new_row, new_col) = transform.apply(row, col, self.width, self.height);
+                transformed.set_cell_state(new_row, new_col, self.cell_state(row, col));
+            }
+        }
+        transformed
+    }
+}
+
+impl Pattern {
+    /// Creates a pattern holding the bounding box of a built-in pattern,
+    /// given as the offsets of its live cells from its center
+    ///
+    /// # Returns
+    ///
+    /// The pattern and the (row, col) of its center cell
+    pub(crate) fn from_offsets(offsets: &[(i64, i64)]) -> (Pattern, (u32, u32)) {
+        let rows = offsets.iter().map(|&(delta_row, _)| delta_row);
+        let columns = offsets.iter().map(|&(_, delta_col)| delta_col);
+        let (top, bottom) = (rows.clone().min().unwrap_or(0), rows.max().unwrap_or(0));
+        let (left, right) = (
+            columns.clone().min().unwrap_or(0),
+            columns.max().unwrap_or(0),
+        );
+
+        let (width, height) = ((right - left + 1) as u32, (bottom - top + 1) as u32);
+        let mut pattern = Pattern::new(width, height);
+        for &(delta_row, delta_col) in offsets {
+            pattern.set_cell_state((delta_row - top) as u32, (delta_col - left) as u32, 1);
+        }
+        (pattern, (-top as u32, -left as u32))
+    }
+
+    /// Returns the index of a cell in the states vector
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell lies outside of the pattern.
+    fn index(&self, row: u32, column: u32) -> usize {
+        assert!(
+            row < self.height && column < self.width,
+            "cell ({}, {}) lies outside of the {}x{} pattern",
+            row,
+            column,
+            self.width,
+            self.height
+        );
+        (row * self.width + column) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paste_modes() {
+        // The combinations of a live, dying and dead pattern cell with a
+        // live, dying and dead universe cell
+        let cells = [1, 2, 0];
+        let combined = |mode: PasteMode| {
+            cells
+                .iter()
+                .flat_map(|&pattern| cells.iter().map(move |&universe| (pattern, universe)))
+                .map(|(pattern, universe)| mode.combine(pattern, universe))
+                .collect::<Vec<_>>()
+        };
+
+        let (keep, dead, alive, dying) = (None, Some(0), Some(1), Some(2));
+        assert_eq!(
+            combined(PasteMode::Or),
+            [alive, alive, alive, keep, keep, keep, keep, keep, keep]
+        );
+        assert_eq!(
+            combined(PasteMode::And),
+            [alive, dead, dead, dead, dead, dead, dead, dead, dead]
+        );
+        assert_eq!(
+            combined(PasteMode::Xor),
+            [dead, alive, alive, alive, dead, dead, alive, dead, dead]
+        );
+        assert_eq!(
+            combined(PasteMode::Copy),
+            [alive, alive, alive, dying, dying, dying, dead, dead, dead]
+        );
+        assert_eq!(
+            combined(PasteMode::LiveOnly),
+            [alive, alive, alive, dying, dying, dying, keep, keep, keep]
+        );
+    }
+
+    #[test]
+    fn test_too_large_patterns_are_rejected() {
+        assert_eq!(
+            Pattern::try_new(70000, 70000),
+            Err(LifeError::UniverseTooLarge {
+                width: 70000,
+                height: 70000
+            })
+        );
+        assert_eq!(Pattern::try_new(0, 70000).unwrap().population(), 0);
+    }
+
+    #[test]
+    fn test_transformed_pattern() {
+        let mut pattern = Pattern::new(3, 1);
+        pattern.set_cell_state(0, 0, 1);
+        pattern.set_cell_state(0, 2, 2);
+
+        let rotated = pattern.transformed(Transform::Rotate90);
+        assert_eq!((rotated.width(), rotated.height()), (1, 3));
+        assert_eq!(rotated.cell_state(0, 0), 1);
+        assert_eq!(rotated.cell_state(2, 0), 2);
+        assert_eq!(rotated.population(), 1);
+    }
+}
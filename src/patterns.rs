@@ -0,0 +1,36 @@
+//! Cell offsets of the built-in patterns, relative to their center cell
+
+/// A horizontal blinker
+pub(crate) const BLINKER_HORIZONTAL: [(i64, i64); 3] = [(0, -1), (0, 0), (0, 1)];
+
+/// A vertical blinker
+pub(crate) const BLINKER_VERTICAL: [(i64, i64); 3] = [(-1, 0), (0, 0), (1, 0)];
+
+/// A glider heading south-east like the catalog's, drawn within a 3 by 3
+/// square
+pub(crate) const GLIDER: [(i64, i64); 5] = [(-1, 0), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// Returns the cells of a pulsar, drawn as twelve blinkers
+pub(crate) fn pulsar() -> Vec<(i64, i64)> {
+    let mut alive_cells = Vec::with_capacity(48);
+
+    // Horizontal blinkers at the top, middle top, middle bottom and bottom
+    for center_col in [-3, 3] {
+        for center_row in [-6, -1, 1, 6] {
+            for (delta_row, delta_col) in BLINKER_HORIZONTAL {
+                alive_cells.push((center_row + delta_row, center_col + delta_col));
+            }
+        }
+    }
+
+    // Vertical blinkers at the top and bottom
+    for center_col in [-6, -1, 1, 6] {
+        for center_row in [-3, 3] {
+            for (delta_row, delta_col) in BLINKER_VERTICAL {
+                alive_cells.push((center_row + delta_row, center_col + delta_col));
+            }
+        }
+    }
+
+    alive_cells
+}
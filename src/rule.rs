@@ -0,0 +1,1054 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+
+use crate::neighborhood::{Neighborhood, MAX_RANGE};
+
+/// A Life-like cellular automaton rule
+///
+/// A rule decides the next state of a cell from its current state and the
+/// number of live cells in its Moore neighborhood. Rules are usually written
+/// as rulestrings in B/S notation, e.g. `B3/S23` for Conway's Game of Life:
+/// a dead cell is born with exactly 3 live neighbors and a live cell survives
+/// with 2 or 3 live neighbors.
+///
+/// Isotropic non-totalistic rules also depend on the arrangement of the
+/// live neighbors, written as Hensel letters after each count, e.g.
+/// `B2-a/S12`: a dead cell is born with 2 live neighbors unless they are
+/// adjacent (`a`) to each other.
+///
+/// Rules of the Generations family add refractory states, written as a
+/// third section, e.g. `B2/S/C3` for Brian's Brain. A live cell that does
+/// not survive passes through the states `2..states` one generation at a
+/// time before it is dead again, and cannot be born or counted as a live
+/// neighbor until then.
+///
+/// Other neighborhoods than the Moore neighborhood are written as a suffix,
+/// `V` for von Neumann and `H` for hexagonal, e.g. `B2/S34H`. Larger than
+/// Life rules count the live cells within a range, e.g.
+/// `R5,C0,M1,S34..58,B34..45,NM` for Bosco's rule: the live cells within 5
+/// rows and columns, including the cell itself (`M1`), are counted, and a
+/// cell survives with 34 to 58 of them and is born with 34 to 45.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    /// Bit `n` is set when a dead cell whose live neighbors are the bits of
+    /// `n` is born, see [`Rule::next_state_for_neighbors`]
+    birth: [u64; 4],
+    /// Bit `n` is set when a live cell whose live neighbors are the bits of
+    /// `n` survives
+    survival: [u64; 4],
+    /// The neighbor counts of a Larger than Life rule, which replace the
+    /// tables when the range of the neighborhood is above 1
+    ranges: Option<CountRanges>,
+    /// The number of cell states, 2 for Life-like rules
+    states: u8,
+    /// The cells counted as neighbors
+    neighborhood: Neighborhood,
+}
+
+/// The neighbor counts of a Larger than Life rule
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct CountRanges {
+    /// The inclusive bounds of the counts for which a dead cell is born
+    birth: (u32, u32),
+    /// The inclusive bounds of the counts for which a live cell survives
+    survival: (u32, u32),
+    /// Whether a cell counts itself as one of its neighbors
+    middle: bool,
+}
+
+impl CountRanges {
+    /// Returns the next state of a cell from its number of live neighbors
+    fn next_state(&self, alive: bool, live_neighbors: u32) -> bool {
+        let (min, max) = if alive { self.survival } else { self.birth };
+        let count = live_neighbors + (alive && self.middle) as u32;
+        (min..=max).contains(&count)
+    }
+}
+
+/// The (row, col) offsets of the neighbors of a cell in the order of their
+/// bits in [`Rule::next_state_for_neighbors`]: N, NE, E, SE, S, SW, W and NW
+pub(crate) const NEIGHBOR_OFFSETS: [(i64, i64); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// The Hensel letters of each neighbor count, in the order they are written
+const HENSEL_LETTERS: [&str; 9] = [
+    "",
+    "ce",
+    "ceaikn",
+    "ceaiknjqry",
+    "ceaiknjqrtwyz",
+    "ceaiknjqry",
+    "ceaikn",
+    "ce",
+    "",
+];
+
+/// One arrangement of live neighbors named by each Hensel letter of 1 to 4
+/// neighbors, with the neighbors as bits in the order N, NE, E, SE, S, SW,
+/// W, NW. The arrangements of 5 to 7 neighbors are the complements of those
+/// of 3 to 1 neighbors with the same letter.
+const HENSEL_NEIGHBORS: [(u8, char, u8); 31] = [
+    (1, 'c', 0x02),
+    (1, 'e', 0x01),
+    (2, 'c', 0x0a),
+    (2, 'e', 0x05),
+    (2, 'a', 0x03),
+    (2, 'i', 0x11),
+    (2, 'k', 0x09),
+    (2, 'n', 0x22),
+    (3, 'c', 0x2a),
+    (3, 'e', 0x15),
+    (3, 'a', 0x07),
+    (3, 'i', 0x83),
+    (3, 'k', 0x25),
+    (3, 'n', 0x0b),
+    (3, 'j', 0x43),
+    (3, 'q', 0x23),
+    (3, 'r', 0x13),
+    (3, 'y', 0x29),
+    (4, 'c', 0xaa),
+    (4, 'e', 0x55),
+    (4, 'a', 0x0f),
+    (4, 'i', 0x1b),
+    (4, 'k', 0x4b),
+    (4, 'n', 0x8b),
+    (4, 'j', 0x53),
+    (4, 'q', 0x27),
+    (4, 'r', 0x17),
+    (4, 't', 0x93),
+    (4, 'w', 0x63),
+    (4, 'y', 0x2b),
+    (4, 'z', 0x33),
+];
+
+/// Returns the table of a totalistic condition from a mask of neighbor counts
+///
+/// Only the neighbors in `neighborhood` are counted, as the bits of
+/// [`Rule::next_state_for_neighbors`].
+const fn totalistic_table(counts: u16, neighborhood: u8) -> [u64; 4] {
+    let mut table = [0; 4];
+    let mut neighbors = 0;
+    while neighbors < 256 {
+        if counts & (1 << (neighbors as u8 & neighborhood).count_ones()) != 0 {
+            table[neighbors / 64] |= 1 << (neighbors % 64);
+        }
+        neighbors += 1;
+    }
+    table
+}
+
+/// Returns whether the bit of an arrangement of neighbors is set in a table
+fn table_contains(table: &[u64; 4], neighbors: u8) -> bool {
+    table[neighbors as usize / 64] & (1 << (neighbors % 64)) != 0
+}
+
+/// Returns every rotation and reflection of an arrangement of neighbors
+fn symmetries(neighbors: u8) -> impl Iterator<Item = u8> {
+    // Neighbors are listed clockwise, so rotating by 90 degrees moves each
+    // one two places on and reflecting reverses their order around N
+    let reflected = (0..8).fold(0u8, |mask, bit| {
+        mask | (((neighbors >> bit) & 1) << ((8 - bit) % 8))
+    });
+    (0..4).flat_map(move |quarter| {
+        [
+            neighbors.rotate_left(2 * quarter),
+            reflected.rotate_left(2 * quarter),
+        ]
+    })
+}
+
+/// Returns the arrangements of neighbors named by a Hensel letter
+///
+/// # Returns
+///
+/// `None` if the letter does not name an arrangement of `count` neighbors
+fn hensel_neighbors(count: u8, letter: char) -> Option<impl Iterator<Item = u8>> {
+    let (lookup, complement) = if count > 4 {
+        (8 - count, true)
+    } else {
+        (count, false)
+    };
+    let &(_, _, neighbors) = HENSEL_NEIGHBORS
+        .iter()
+        .find(|&&(n, l, _)| n == lookup && l == letter)?;
+    let neighbors = if complement { !neighbors } else { neighbors };
+    Some(symmetries(neighbors))
+}
+
+impl Rule {
+    /// Returns the rule for Conway's Game of Life (`B3/S23`)
+    pub const fn conway() -> Rule {
+        Rule {
+            birth: totalistic_table(1 << 3, 0xff),
+            survival: totalistic_table((1 << 2) | (1 << 3), 0xff),
+            ranges: None,
+            states: 2,
+            neighborhood: Neighborhood::Moore(1),
+        }
+    }
+
+    /// Creates a rule from the neighbor counts that cause birth and survival
+    ///
+    /// # Arguments
+    ///
+    /// * `birth` - Neighbor counts for which a dead cell becomes alive
+    /// * `survival` - Neighbor counts for which a live cell stays alive
+    ///
+    /// # Panics
+    ///
+    /// Every neighbor count must be between 0 and 8 inclusive.
+    pub fn new(birth: &[u8], survival: &[u8]) -> Rule {
+        Rule::with_neighborhood(birth, survival, Neighborhood::Moore(1))
+    }
+
+    /// Creates a rule that counts the live cells of a range 1 neighborhood
+    ///
+    /// # Arguments
+    ///
+    /// * `birth` - Neighbor counts for which a dead cell becomes alive
+    /// * `survival` - Neighbor counts for which a live cell stays alive
+    /// * `neighborhood` - The cells counted as neighbors
+    ///
+    /// # Panics
+    ///
+    /// The neighborhood must have a range of 1, see
+    /// [`Rule::larger_than_life`] for larger ranges, and every neighbor
+    /// count must be at most the size of the neighborhood.
+    pub fn with_neighborhood(birth: &[u8], survival: &[u8], neighborhood: Neighborhood) -> Rule {
+        assert!(
+            neighborhood.range() == 1,
+            "Neighborhoods with a range above 1 need a Larger than Life rule"
+        );
+        let size = neighborhood.size() as u8;
+        let mask = |counts: &[u8]| {
+            counts.iter().fold(0u16, |mask, &count| {
+                assert!(
+                    count <= size,
+                    "Neighbor counts must be between 0 and {}",
+                    size
+                );
+                mask | (1 << count)
+            })
+        };
+
+        Rule {
+            birth: totalistic_table(mask(birth), neighborhood.mask()),
+            survival: totalistic_table(mask(survival), neighborhood.mask()),
+            ranges: None,
+            states: 2,
+            neighborhood,
+        }
+    }
+
+    /// Creates a Larger than Life rule, which counts the live cells within
+    /// a range of each cell
+    ///
+    /// # Arguments
+    ///
+    /// * `neighborhood` - A Moore or von Neumann neighborhood of any range
+    /// * `birth` - The neighbor counts for which a dead cell becomes alive
+    /// * `survival` - The neighbor counts for which a live cell stays alive
+    /// * `middle` - Whether a cell counts itself as one of its neighbors
+    ///
+    /// # Panics
+    ///
+    /// The neighborhood cannot be hexagonal and its range must be between
+    /// 1 and 500.
+    pub fn larger_than_life(
+        neighborhood: Neighborhood,
+        birth: RangeInclusive<u32>,
+        survival: RangeInclusive<u32>,
+        middle: bool,
+    ) -> Rule {
+        assert!(
+            neighborhood != Neighborhood::Hexagonal,
+            "Larger than Life rules cannot use the hexagonal neighborhood"
+        );
+        assert!(
+            (1..=MAX_RANGE).contains(&neighborhood.range()),
+            "The range of a neighborhood must be between 1 and {}",
+            MAX_RANGE
+        );
+
+        let ranges = CountRanges {
+            birth: (*birth.start(), *birth.end()),
+            survival: (*survival.start(), *survival.end()),
+            middle,
+        };
+        if neighborhood.range() > 1 {
+            return Rule {
+                birth: [0; 4],
+                survival: [0; 4],
+                ranges: Some(ranges),
+                states: 2,
+                neighborhood,
+            };
+        }
+
+        // A range of 1 fits in the tables of the other rules
+        let counts = |alive: bool| {
+            (0..=neighborhood.size() as u8)
+                .filter(|&count| ranges.next_state(alive, count as u32))
+                .collect::<Vec<u8>>()
+        };
+        Rule::with_neighborhood(&counts(false), &counts(true), neighborhood)
+    }
+
+    /// Creates a Generations rule with refractory states
+    ///
+    /// # Arguments
+    ///
+    /// * `birth` - Neighbor counts for which a dead cell becomes alive
+    /// * `survival` - Neighbor counts for which a live cell stays alive
+    /// * `states` - The number of cell states, counting dead and alive
+    ///
+    /// # Panics
+    ///
+    /// Every neighbor count must be between 0 and 8 inclusive, and there
+    /// must be at least 2 states.
+    pub fn generations(birth: &[u8], survival: &[u8], states: u8) -> Rule {
+        assert!(states >= 2, "A rule must have at least 2 states");
+        Rule {
+            states,
+            ..Rule::new(birth, survival)
+        }
+    }
+
+    /// Parses a rulestring such as `B36/S23`, `S23/B3` or `23/3`
+    ///
+    /// Both the B/S notation and the older S/B notation without letters
+    /// are accepted. Letters are case insensitive and either half may be
+    /// empty, as in `B2/S` (Seeds). Generations rules add the number of
+    /// states as a third section, as in `B2/S/C3` or `/2/3`.
+    ///
+    /// A neighbor count may be followed by lowercase Hensel letters to only
+    /// include the arrangements of neighbors they name, as in `B2a`, or by
+    /// `-` and letters to exclude them, as in `B2-a`.
+    ///
+    /// A trailing `V` or `H` selects the von Neumann or hexagonal
+    /// neighborhood, as in `B2/S34H`, which only take counts without letters.
+    /// Larger than Life rules are written as comma separated parameters, as
+    /// in `R5,C0,M1,S34..58,B34..45,NM`, where `C` and `M` default to 0 and
+    /// the neighborhood `N` to `M` (Moore) rather than `N` (von Neumann).
+    ///
+    /// # Arguments
+    ///
+    /// * `rulestring` - The rulestring to parse
+    pub fn parse(rulestring: &str) -> Result<Rule, RuleParseError> {
+        let rulestring = rulestring.trim();
+        if rulestring.is_empty() {
+            return Err(RuleParseError::Empty);
+        }
+
+        let mut chars = rulestring.chars();
+        if matches!(chars.next(), Some('R' | 'r'))
+            && chars.next().is_some_and(|c| c.is_ascii_digit())
+        {
+            return parse_larger_than_life(rulestring);
+        }
+
+        let (rulestring, neighborhood) = match rulestring.chars().last() {
+            Some('V' | 'v') => (
+                &rulestring[..rulestring.len() - 1],
+                Neighborhood::VonNeumann(1),
+            ),
+            Some('H' | 'h') => (&rulestring[..rulestring.len() - 1], Neighborhood::Hexagonal),
+            _ => (rulestring, Neighborhood::Moore(1)),
+        };
+
+        let parts: Vec<&str> = if rulestring.contains('/') {
+            rulestring.split('/').collect()
+        } else {
+            // Also accept the compact `B3S23` form
+            match rulestring.find(['S', 's']) {
+                Some(split) if split > 0 => vec![&rulestring[..split], &rulestring[split..]],
+                _ => return Err(RuleParseError::MissingSeparator),
+            }
+        };
+        if parts.len() > 3 {
+            return Err(RuleParseError::InvalidCharacter('/'));
+        }
+
+        let mut birth = None;
+        let mut survival = None;
+        let mut states = None;
+        for (position, part) in parts.iter().enumerate() {
+            let (section, digits) = match part.chars().next() {
+                Some('B' | 'b') => ('B', &part[1..]),
+                Some('S' | 's') => ('S', &part[1..]),
+                Some('C' | 'c' | 'G' | 'g') => ('C', &part[1..]),
+                // Without letters, the survival counts come first
+                _ => (['S', 'B', 'C'][position], *part),
+            };
+
+            if section == 'C' {
+                if states.is_some() {
+                    return Err(RuleParseError::DuplicateSection(section));
+                }
+                states = Some(parse_states(digits)?);
+                continue;
+            }
+
+            let slot = if section == 'B' {
+                &mut birth
+            } else {
+                &mut survival
+            };
+            if slot.is_some() {
+                return Err(RuleParseError::DuplicateSection(section));
+            }
+            *slot = Some(parse_conditions(digits, neighborhood)?);
+        }
+
+        Ok(Rule {
+            birth: birth.unwrap_or([0; 4]),
+            survival: survival.unwrap_or([0; 4]),
+            ranges: None,
+            states: states.unwrap_or(2),
+            neighborhood,
+        })
+    }
+
+    /// Returns the number of cell states, counting dead and alive
+    ///
+    /// This is 2 for Life-like rules and more for Generations rules.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// Returns the cells counted as the neighbors of a cell
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    /// Returns whether a dead cell with `live_neighbors` live neighbors is born
+    ///
+    /// For non-totalistic rules, this is whether it is born for any
+    /// arrangement of its live neighbors.
+    pub fn is_birth(&self, live_neighbors: u8) -> bool {
+        match &self.ranges {
+            Some(ranges) => ranges.next_state(false, live_neighbors as u32),
+            None => any_with_count(&self.birth, live_neighbors, self.neighborhood.mask()),
+        }
+    }
+
+    /// Returns whether a live cell with `live_neighbors` live neighbors survives
+    ///
+    /// For non-totalistic rules, this is whether it survives for any
+    /// arrangement of its live neighbors. The cell itself is not counted,
+    /// even by Larger than Life rules that count it (`M1`).
+    pub fn is_survival(&self, live_neighbors: u8) -> bool {
+        match &self.ranges {
+            Some(ranges) => ranges.next_state(true, live_neighbors as u32),
+            None => any_with_count(&self.survival, live_neighbors, self.neighborhood.mask()),
+        }
+    }
+
+    /// Returns whether the next state of a cell only depends on its number
+    /// of live neighbors, and not on their arrangement
+    pub fn is_totalistic(&self) -> bool {
+        let mask = self.neighborhood.mask();
+        self.ranges.is_some()
+            || [&self.birth, &self.survival].into_iter().all(|table| {
+                let mut by_count = [None; 9];
+                (0..=255u8).all(|neighbors| {
+                    let contains = table_contains(table, neighbors);
+                    let count = (neighbors & mask).count_ones() as usize;
+                    *by_count[count].get_or_insert(contains) == contains
+                })
+            })
+    }
+
+    /// Returns the next state of a cell
+    ///
+    /// For non-totalistic rules, use [`Rule::next_state_for_neighbors`].
+    ///
+    /// # Arguments
+    ///
+    /// * `alive` - Whether the cell is currently alive
+    /// * `live_neighbors` - The number of live cells in the cell's neighborhood
+    pub fn next_state(&self, alive: bool, live_neighbors: u8) -> bool {
+        if alive {
+            self.is_survival(live_neighbors)
+        } else {
+            self.is_birth(live_neighbors)
+        }
+    }
+
+    /// Returns the next state of a cell from the arrangement of its live
+    /// neighbors
+    ///
+    /// # Arguments
+    ///
+    /// * `alive` - Whether the cell is currently alive
+    /// * `neighbors` - The live neighbors of the cell, as the bits 0 to 7
+    ///   for the neighbors to the N, NE, E, SE, S, SW, W and NW. Only the
+    ///   bits of neighbors in the rule's neighborhood are counted.
+    pub fn next_state_for_neighbors(&self, alive: bool, neighbors: u8) -> bool {
+        match (&self.ranges, alive) {
+            (Some(ranges), _) => ranges.next_state(alive, neighbors.count_ones()),
+            (None, true) => table_contains(&self.survival, neighbors),
+            (None, false) => table_contains(&self.birth, neighbors),
+        }
+    }
+
+    /// Returns the next state of a cell from the number of live cells in
+    /// its neighborhood, which may be above 8 for Larger than Life rules
+    ///
+    /// # Arguments
+    ///
+    /// * `alive` - Whether the cell is currently alive
+    /// * `live_neighbors` - The number of live neighbors, not counting the cell
+    pub(crate) fn next_state_for_count(&self, alive: bool, live_neighbors: u32) -> bool {
+        match &self.ranges {
+            Some(ranges) => ranges.next_state(alive, live_neighbors),
+            None => live_neighbors <= 8 && self.next_state(alive, live_neighbors as u8),
+        }
+    }
+
+    /// Returns the birth conditions as they are written in a rulestring,
+    /// e.g. `2-a3` for `B2-a3/S23`
+    pub(crate) fn birth_conditions(&self) -> String {
+        write_conditions(&self.birth, self.neighborhood)
+    }
+
+    /// Returns the survival conditions as they are written in a rulestring
+    pub(crate) fn survival_conditions(&self) -> String {
+        write_conditions(&self.survival, self.neighborhood)
+    }
+
+    /// Returns the letter that follows a B/S rulestring to select its
+    /// neighborhood, if it is not the Moore neighborhood
+    pub(crate) fn neighborhood_suffix(&self) -> &'static str {
+        match self.neighborhood {
+            Neighborhood::VonNeumann(_) => "V",
+            Neighborhood::Hexagonal => "H",
+            Neighborhood::Moore(_) => "",
+        }
+    }
+
+    /// Returns whether the rule is a Larger than Life rule with a range
+    /// above 1, which cannot be written in B/S notation
+    pub(crate) fn is_larger_than_life(&self) -> bool {
+        self.ranges.is_some()
+    }
+}
+
+/// Returns whether any arrangement of `count` neighbors is set in a table
+///
+/// Only arrangements of the neighbors in `neighborhood` are considered.
+fn any_with_count(table: &[u64; 4], count: u8, neighborhood: u8) -> bool {
+    (0..=255u8).any(|neighbors| {
+        neighbors & !neighborhood == 0
+            && neighbors.count_ones() == count as u32
+            && table_contains(table, neighbors)
+    })
+}
+
+/// Parses the neighbor counts of a birth or survival section, each followed
+/// by optional Hensel letters, into a table
+///
+/// Hensel letters only name arrangements of the Moore neighborhood, so
+/// other neighborhoods take plain counts.
+fn parse_conditions(digits: &str, neighborhood: Neighborhood) -> Result<[u64; 4], RuleParseError> {
+    if neighborhood != Neighborhood::Moore(1) {
+        let counts = digits
+            .chars()
+            .try_fold(0u16, |counts, c| match c.to_digit(10) {
+                Some(count) if count <= neighborhood.size() => Ok(counts | (1 << count)),
+                _ => Err(RuleParseError::InvalidCharacter(c)),
+            })?;
+        return Ok(totalistic_table(counts, neighborhood.mask()));
+    }
+
+    let mut table = [0; 4];
+    let mut chars = digits.chars().peekable();
+    while let Some(c) = chars.next() {
+        let count = match c.to_digit(10) {
+            Some(count) if count <= 8 => count as u8,
+            _ => return Err(RuleParseError::InvalidCharacter(c)),
+        };
+
+        let excluded = chars.next_if_eq(&'-').is_some();
+        let mut named = [0; 4];
+        let mut has_letters = false;
+        while let Some(letter) = chars.next_if(char::is_ascii_lowercase) {
+            let arrangements =
+                hensel_neighbors(count, letter).ok_or(RuleParseError::InvalidCharacter(letter))?;
+            for neighbors in arrangements {
+                named[neighbors as usize / 64] |= 1 << (neighbors % 64);
+            }
+            has_letters = true;
+        }
+        if excluded && !has_letters {
+            return Err(RuleParseError::InvalidCharacter('-'));
+        }
+
+        for neighbors in (0..=255u8).filter(|n| n.count_ones() == count as u32) {
+            if !has_letters || table_contains(&named, neighbors) != excluded {
+                table[neighbors as usize / 64] |= 1 << (neighbors % 64);
+            }
+        }
+    }
+    Ok(table)
+}
+
+/// Writes the neighbor counts set in a table, with the Hensel letters of
+/// the counts for which only some arrangements are set
+fn write_conditions(table: &[u64; 4], neighborhood: Neighborhood) -> String {
+    let mut conditions = String::new();
+    for count in 0..=neighborhood.size().min(8) as u8 {
+        if !any_with_count(table, count, neighborhood.mask()) {
+            continue;
+        }
+        conditions.push(char::from(b'0' + count));
+        if neighborhood != Neighborhood::Moore(1) {
+            continue;
+        }
+
+        let letters = HENSEL_LETTERS[count as usize];
+        let (present, absent): (String, String) = letters.chars().partition(|&letter| {
+            hensel_neighbors(count, letter)
+                .is_some_and(|mut arrangements| arrangements.all(|n| table_contains(table, n)))
+        });
+        if absent.is_empty() {
+            continue;
+        }
+        if absent.len() < present.len() {
+            conditions.push('-');
+            conditions.push_str(&absent);
+        } else {
+            conditions.push_str(&present);
+        }
+    }
+    conditions
+}
+
+/// Parses a Larger than Life rulestring such as `R5,C0,M1,S34..58,B34..45,NM`
+fn parse_larger_than_life(rulestring: &str) -> Result<Rule, RuleParseError> {
+    let (mut range, mut states, mut middle) = (None, None, None);
+    let (mut survival, mut birth, mut von_neumann) = (None, None, None);
+    for parameter in rulestring.split(',') {
+        let parameter = parameter.trim();
+        let mut chars = parameter.chars();
+        let name = match chars.next() {
+            Some(name) => name.to_ascii_uppercase(),
+            None => return Err(RuleParseError::InvalidCharacter(',')),
+        };
+        let value = chars.as_str();
+
+        match name {
+            'R' => fill(&mut range, name, parse_count(name, value)?)?,
+            'C' => {
+                let value = match value {
+                    "0" => 2,
+                    _ => parse_states(value)?,
+                };
+                fill(&mut states, name, value)?
+            }
+            'M' => match value {
+                "0" | "1" => fill(&mut middle, name, value == "1")?,
+                _ => return Err(RuleParseError::InvalidParameter(name)),
+            },
+            'S' => fill(&mut survival, name, parse_interval(name, value)?)?,
+            'B' => fill(&mut birth, name, parse_interval(name, value)?)?,
+            'N' => match value {
+                "M" | "m" => fill(&mut von_neumann, name, false)?,
+                "N" | "n" => fill(&mut von_neumann, name, true)?,
+                _ => return Err(RuleParseError::InvalidParameter(name)),
+            },
+            _ => return Err(RuleParseError::InvalidCharacter(name)),
+        }
+    }
+
+    let range = range.ok_or(RuleParseError::MissingSection('R'))?;
+    if !(1..=MAX_RANGE).contains(&range) {
+        return Err(RuleParseError::InvalidParameter('R'));
+    }
+    let neighborhood = if von_neumann.unwrap_or(false) {
+        Neighborhood::VonNeumann(range)
+    } else {
+        Neighborhood::Moore(range)
+    };
+    let survival = survival.ok_or(RuleParseError::MissingSection('S'))?;
+    let birth = birth.ok_or(RuleParseError::MissingSection('B'))?;
+
+    Ok(Rule {
+        states: states.unwrap_or(2),
+        ..Rule::larger_than_life(neighborhood, birth, survival, middle.unwrap_or(false))
+    })
+}
+
+/// Stores the value of a section, which may only be given once
+fn fill<T>(slot: &mut Option<T>, section: char, value: T) -> Result<(), RuleParseError> {
+    if slot.replace(value).is_some() {
+        return Err(RuleParseError::DuplicateSection(section));
+    }
+    Ok(())
+}
+
+/// Parses the number of a Larger than Life parameter
+fn parse_count(name: char, value: &str) -> Result<u32, RuleParseError> {
+    value
+        .parse()
+        .map_err(|_| RuleParseError::InvalidParameter(name))
+}
+
+/// Parses an interval of neighbor counts such as `34..58`, or a single count
+fn parse_interval(name: char, value: &str) -> Result<RangeInclusive<u32>, RuleParseError> {
+    match value.split_once("..") {
+        Some((min, max)) => Ok(parse_count(name, min)?..=parse_count(name, max)?),
+        None => {
+            let count = parse_count(name, value)?;
+            Ok(count..=count)
+        }
+    }
+}
+
+/// Parses the number of states of a Generations rule
+fn parse_states(digits: &str) -> Result<u8, RuleParseError> {
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(RuleParseError::InvalidCharacter(c));
+    }
+    match digits.parse() {
+        Ok(states) if states >= 2 => Ok(states),
+        _ => Err(RuleParseError::InvalidStates),
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::conway()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = RuleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Rule::parse(s)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ranges) = &self.ranges {
+            let shape = match self.neighborhood {
+                Neighborhood::VonNeumann(_) => 'N',
+                _ => 'M',
+            };
+            return write!(
+                f,
+                "R{},C{},M{},S{}..{},B{}..{},N{}",
+                self.neighborhood.range(),
+                if self.states > 2 { self.states } else { 0 },
+                ranges.middle as u8,
+                ranges.survival.0,
+                ranges.survival.1,
+                ranges.birth.0,
+                ranges.birth.1,
+                shape
+            );
+        }
+
+        write!(
+            f,
+            "B{}/S{}",
+            self.birth_conditions(),
+            self.survival_conditions()
+        )?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        write!(f, "{}", self.neighborhood_suffix())
+    }
+}
+
+/// An error returned when a rulestring cannot be parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleParseError {
+    /// The rulestring was empty
+    Empty,
+    /// The rulestring did not separate the birth and survival conditions
+    MissingSeparator,
+    /// The birth or survival conditions were given more than once
+    DuplicateSection(char),
+    /// A character other than a neighbor count between 0 and 8 was found
+    InvalidCharacter(char),
+    /// The number of states of a Generations rule was not between 2 and 255
+    InvalidStates,
+    /// A required section of a Larger than Life rule was not given
+    MissingSection(char),
+    /// The value of a Larger than Life parameter was invalid, such as a
+    /// range that is not between 1 and 500
+    InvalidParameter(char),
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleParseError::Empty => write!(f, "rulestring is empty"),
+            RuleParseError::MissingSeparator => {
+                write!(f, "rulestring must separate birth and survival with '/'")
+            }
+            RuleParseError::DuplicateSection(section) => {
+                write!(f, "rulestring contains more than one '{}' section", section)
+            }
+            RuleParseError::InvalidCharacter(c) => {
+                write!(f, "invalid character '{}' in rulestring", c)
+            }
+            RuleParseError::InvalidStates => {
+                write!(f, "number of states must be between 2 and 255")
+            }
+            RuleParseError::MissingSection(section) => {
+                write!(f, "rulestring is missing the '{}' section", section)
+            }
+            RuleParseError::InvalidParameter(parameter) => {
+                write!(f, "invalid value for the '{}' parameter", parameter)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RuleParseError {}
+
+/// Serializes the rule as its rulestring
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rule {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rule {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<RuleParseError> for JsValue {
+    fn from(error: RuleParseError) -> Self {
+        js_sys::Error::new(&error.to_string()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bs_notation() {
+        let highlife = Rule::parse("B36/S23").unwrap();
+        assert_eq!(highlife, Rule::new(&[3, 6], &[2, 3]));
+        assert_eq!(Rule::parse("b3/s23").unwrap(), Rule::conway());
+        assert_eq!(Rule::parse("S23/B3").unwrap(), Rule::conway());
+        assert_eq!(Rule::parse("B3S23").unwrap(), Rule::conway());
+    }
+
+    #[test]
+    fn test_parse_sb_notation() {
+        assert_eq!(Rule::parse("23/3").unwrap(), Rule::conway());
+
+        let day_and_night = Rule::parse("34678/3678").unwrap();
+        assert_eq!(day_and_night, Rule::new(&[3, 6, 7, 8], &[3, 4, 6, 7, 8]));
+    }
+
+    #[test]
+    fn test_parse_empty_sections() {
+        let seeds = Rule::parse("B2/S").unwrap();
+        assert_eq!(seeds, Rule::new(&[2], &[]));
+        assert_eq!(Rule::parse("/2").unwrap(), seeds);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Rule::parse(""), Err(RuleParseError::Empty));
+        assert_eq!(Rule::parse("B3"), Err(RuleParseError::MissingSeparator));
+        assert_eq!(
+            Rule::parse("B39/S23"),
+            Err(RuleParseError::InvalidCharacter('9'))
+        );
+        assert_eq!(
+            Rule::parse("B3/B23"),
+            Err(RuleParseError::DuplicateSection('B'))
+        );
+    }
+
+    #[test]
+    fn test_parse_generations() {
+        let brians_brain = Rule::parse("B2/S/C3").unwrap();
+        assert_eq!(brians_brain, Rule::generations(&[2], &[], 3));
+        assert_eq!(brians_brain.states(), 3);
+        assert_eq!(Rule::parse("/2/3").unwrap(), brians_brain);
+        assert_eq!(Rule::parse("b2/s/g3").unwrap(), brians_brain);
+
+        let star_wars = Rule::parse("345/2/4").unwrap();
+        assert_eq!(star_wars, Rule::generations(&[2], &[3, 4, 5], 4));
+
+        assert_eq!(Rule::parse("B3/S23/C2").unwrap(), Rule::conway());
+        assert_eq!(Rule::parse("B2/S/C1"), Err(RuleParseError::InvalidStates));
+        assert_eq!(Rule::parse("B2/S/C256"), Err(RuleParseError::InvalidStates));
+        assert_eq!(
+            Rule::parse("B2/S/C3/C4"),
+            Err(RuleParseError::InvalidCharacter('/'))
+        );
+    }
+
+    #[test]
+    fn test_hensel_letters_partition_neighbors() {
+        for count in 0..=8u8 {
+            let mut letter_of = [None; 256];
+            for letter in HENSEL_LETTERS[count as usize].chars() {
+                for neighbors in hensel_neighbors(count, letter).unwrap() {
+                    assert_eq!(neighbors.count_ones(), count as u32);
+                    let previous = letter_of[neighbors as usize].replace(letter);
+                    assert!(previous.is_none() || previous == Some(letter));
+                }
+            }
+
+            // Every arrangement is named by a letter, except for 0 and 8
+            // neighbors which have a single arrangement
+            let unnamed = (0..=255u8)
+                .filter(|n| n.count_ones() == count as u32)
+                .filter(|&n| letter_of[n as usize].is_none())
+                .count();
+            assert_eq!(unnamed, if count == 0 || count == 8 { 1 } else { 0 });
+        }
+    }
+
+    #[test]
+    fn test_parse_isotropic() {
+        let rule = Rule::parse("B2-a/S12").unwrap();
+        assert!(!rule.is_totalistic());
+
+        // Two adjacent neighbors to the N and NE do not cause a birth, while
+        // two opposite neighbors to the N and S do
+        assert!(!rule.next_state_for_neighbors(false, 0b0000_0011));
+        assert!(rule.next_state_for_neighbors(false, 0b0001_0001));
+        assert!(rule.next_state_for_neighbors(true, 0b1000_0000));
+
+        assert_eq!(
+            Rule::parse("B2-/S"),
+            Err(RuleParseError::InvalidCharacter('-'))
+        );
+        assert_eq!(
+            Rule::parse("B1k/S"),
+            Err(RuleParseError::InvalidCharacter('k'))
+        );
+        assert_eq!(Rule::parse("B3cekainyqjr/S23").unwrap(), Rule::conway());
+        assert!(Rule::conway().is_totalistic());
+    }
+
+    #[test]
+    fn test_parse_neighborhoods() {
+        let hexagonal = Rule::parse("B2/S34H").unwrap();
+        assert_eq!(hexagonal.neighborhood(), Neighborhood::Hexagonal);
+        assert_eq!(
+            hexagonal,
+            Rule::with_neighborhood(&[2], &[3, 4], Neighborhood::Hexagonal)
+        );
+        assert!(hexagonal.is_totalistic());
+
+        // The NE and SW neighbors are not counted
+        assert!(hexagonal.next_state_for_neighbors(false, 0b0010_0101));
+        assert!(!hexagonal.next_state_for_neighbors(false, 0b0000_0011));
+
+        let von_neumann = Rule::parse("b1/s012v").unwrap();
+        assert_eq!(von_neumann.neighborhood(), Neighborhood::VonNeumann(1));
+        assert!(von_neumann.is_birth(1));
+        assert!(!von_neumann.is_birth(2));
+
+        assert_eq!(
+            Rule::parse("B5/S34V"),
+            Err(RuleParseError::InvalidCharacter('5'))
+        );
+        assert_eq!(
+            Rule::parse("B2a/S34H"),
+            Err(RuleParseError::InvalidCharacter('a'))
+        );
+    }
+
+    #[test]
+    fn test_parse_larger_than_life() {
+        let bosco = Rule::parse("R5,C0,M1,S34..58,B34..45,NM").unwrap();
+        assert_eq!(
+            bosco,
+            Rule::larger_than_life(Neighborhood::Moore(5), 34..=45, 34..=58, true)
+        );
+        assert_eq!(bosco.neighborhood().size(), 120);
+        assert!(bosco.is_birth(34) && !bosco.is_birth(46));
+        // The cell counts itself
+        assert!(bosco.is_survival(33) && !bosco.is_survival(58));
+        assert_eq!(Rule::parse("r5,b34..45,s34..58,m1").unwrap(), bosco);
+
+        let generations = Rule::parse("R3,C4,M0,S2..5,B3..4,NN").unwrap();
+        assert_eq!(generations.states(), 4);
+        assert_eq!(generations.neighborhood(), Neighborhood::VonNeumann(3));
+
+        // A range of 1 is a Life-like rule
+        assert_eq!(
+            Rule::parse("R1,C0,M1,S3..4,B3..3,NM").unwrap(),
+            Rule::conway()
+        );
+
+        assert_eq!(
+            Rule::parse("R5,C0,M1,S34..58"),
+            Err(RuleParseError::MissingSection('B'))
+        );
+        assert_eq!(
+            Rule::parse("R501,C0,M1,S34..58,B34..45"),
+            Err(RuleParseError::InvalidParameter('R'))
+        );
+        assert_eq!(
+            Rule::parse("R5,C0,M2,S34..58,B34..45"),
+            Err(RuleParseError::InvalidParameter('M'))
+        );
+        assert_eq!(
+            Rule::parse("R5,S34..x,B34..45"),
+            Err(RuleParseError::InvalidParameter('S'))
+        );
+        assert_eq!(
+            Rule::parse("R5,R6,S34..58,B34..45"),
+            Err(RuleParseError::DuplicateSection('R'))
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for rulestring in [
+            "B3/S23",
+            "B36/S23",
+            "B3678/S34678",
+            "B2/S",
+            "B/S012345678",
+            "B2/S/C3",
+            "B2/S345/C4",
+            "B2-a/S12",
+            "B2ce3i/S1e2-kn34t",
+            "B35y6e/S4-c5q/C5",
+            "B2/S34H",
+            "B13/S012V",
+            "B2/S/C3H",
+            "R5,C0,M1,S34..58,B34..45,NM",
+            "R3,C4,M0,S2..5,B3..4,NN",
+        ] {
+            let rule: Rule = rulestring.parse().unwrap();
+            assert_eq!(rule.to_string(), rulestring);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Serialization of universes with serde
+//!
+//! A universe is written as its dimensions, rule, topology and generation,
+//! with its cells packed eight to a byte, in row-major order starting from
+//! the lowest bit, and encoded as base64. The states of dying cells under a
+//! Generations rule are only written while some cell is dying.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use fixedbitset::FixedBitSet;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{LifeError, Rule, Topology, Universe};
+
+/// The serialized form of a universe
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Universe")]
+struct UniverseData {
+    width: u32,
+    height: u32,
+    #[serde(default)]
+    rule: Rule,
+    #[serde(default)]
+    topology: Topology,
+    #[serde(default)]
+    generation: u64,
+    /// The base64 of the packed cells
+    cells: String,
+    /// The base64 of the state of every cell, while some cell is dying
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dying: Option<String>,
+}
+
+impl Serialize for Universe {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut packed = vec![0u8; self.cells.len().div_ceil(8)];
+        for idx in self.cells.ones() {
+            packed[idx / 8] |= 1 << (idx % 8);
+        }
+        let dying = self
+            .dying
+            .iter()
+            .any(|&state| state > 0)
+            .then(|| BASE64.encode(&self.dying));
+
+        UniverseData {
+            width: self.width,
+            height: self.height,
+            rule: self.rule,
+            topology: self.topology,
+            generation: self.generation,
+            cells: BASE64.encode(packed),
+            dying,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Universe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = UniverseData::deserialize(deserializer)?;
+        let size = data.width as usize * data.height as usize;
+
+        let packed = BASE64.decode(&data.cells).map_err(D::Error::custom)?;
+        if packed.len() != size.div_ceil(8) {
+            return Err(D::Error::invalid_length(
+                packed.len(),
+                &"one bit for every cell",
+            ));
+        }
+        let mut cells = FixedBitSet::with_capacity(size);
+        for idx in 0..size {
+            cells.set(idx, packed[idx / 8] & (1 << (idx % 8)) != 0);
+        }
+
+        let dying = match data.dying {
+            Some(dying) => BASE64.decode(dying).map_err(D::Error::custom)?,
+            None => Vec::new(),
+        };
+        if !dying.is_empty() && dying.len() != size {
+            return Err(D::Error::invalid_length(
+                dying.len(),
+                &"one state for every cell",
+            ));
+        }
+        let states = data.rule.states();
+        if let Some(&state) = dying.iter().find(|&&state| state == 1 || state >= states) {
+            return Err(D::Error::custom(LifeError::InvalidState { state, states }));
+        }
+
+        let mut universe = Universe::try_with_rule(data.width, data.height, data.rule)
+            .map_err(D::Error::custom)?;
+        universe.cells = cells;
+        universe.dying = dying;
+        universe.topology = data.topology;
+        universe.generation = data.generation;
+        Ok(universe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Simulation;
+
+    #[test]
+    fn test_round_trip_formats() {
+        let mut universe = Universe::with_rule(13, 7, Rule::parse("B2/S/C4").unwrap());
+        universe.set_topology(Topology::KleinBottle);
+        universe.randomize();
+        universe.tick();
+        universe.tick();
+        assert!(universe.cell_states().iter().any(|&state| state > 1));
+
+        let assert_same = |restored: Universe| {
+            assert_eq!(restored.width(), 13);
+            assert_eq!(restored.height(), 7);
+            assert_eq!(restored.rule(), universe.rule());
+            assert_eq!(restored.topology(), universe.topology());
+            assert_eq!(restored.generation(), 2);
+            assert_eq!(restored.cell_states(), universe.cell_states());
+        };
+
+        let json = serde_json::to_string(&universe).unwrap();
+        assert_same(serde_json::from_str(&json).unwrap());
+        let bytes = bincode::serialize(&universe).unwrap();
+        assert_same(bincode::deserialize(&bytes).unwrap());
+        let bytes = rmp_serde::to_vec(&universe).unwrap();
+        assert_same(rmp_serde::from_slice(&bytes).unwrap());
+    }
+
+    #[test]
+    fn test_json_layout() {
+        let mut universe = Universe::new_empty(4, 3);
+        universe.set_cells(&[(0, 0), (2, 3)]);
+
+        let json = serde_json::to_value(&universe).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "width": 4,
+                "height": 3,
+                "rule": "B3/S23",
+                "topology": "torus",
+                "generation": 0,
+                "cells": "AQg=",
+            })
+        );
+
+        // Everything but the dimensions and cells can be left out
+        let restored: Universe =
+            serde_json::from_str(r#"{"width": 4, "height": 3, "cells": "AQg="}"#).unwrap();
+        assert_eq!(restored.get_cells(), universe.get_cells());
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        let parse = |json: &str| serde_json::from_str::<Universe>(json).err().unwrap();
+
+        assert!(parse(r#"{"width": 4, "height": 3, "cells": "AQ=="}"#)
+            .to_string()
+            .contains("one bit for every cell"));
+        assert!(parse(r#"{"width": 4, "height": 3, "cells": "!"}"#)
+            .to_string()
+            .contains("Invalid"));
+        assert!(
+            parse(r#"{"width": 1, "height": 1, "rule": "B9", "cells": "AA=="}"#)
+                .to_string()
+                .contains("rule")
+        );
+        assert!(parse(
+            r#"{"width": 1, "height": 1, "rule": "B2/S/C3", "cells": "AA==", "dying": "Aw=="}"#
+        )
+        .to_string()
+        .contains("state 3"));
+    }
+}
@@ -0,0 +1,20 @@
+/// A cellular automaton that can be advanced by any number of generations
+///
+/// This is the stepping API shared by the dense [`Universe`](crate::Universe),
+/// the unbounded [`SparseUniverse`](crate::SparseUniverse) and the
+/// [`HashLife`](crate::HashLife) engine, so code driving a simulation
+/// does not need to know how its cells are stored.
+pub trait Simulation {
+    /// Advances the simulation by the given number of generations
+    ///
+    /// # Arguments
+    ///
+    /// * `generations` - The number of generations to advance
+    fn step(&mut self, generations: u64);
+
+    /// Returns the number of generations advanced since the simulation began
+    fn generation(&self) -> u64;
+
+    /// Returns the number of live cells
+    fn population(&self) -> u64;
+}
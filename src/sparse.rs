@@ -0,0 +1,619 @@
+//! Unbounded universes that only store their live cells
+
+use std::collections::{HashMap, HashSet};
+
+use crate::rule::NEIGHBOR_OFFSETS;
+use crate::{pattern, patterns, LifeError, Pattern, Rule, Simulation, Transform, Universe};
+
+/// A Life-like universe on an unbounded plane
+///
+/// Only the coordinates of live cells are stored, so the universe grows and
+/// shrinks with its pattern and spaceships travel forever instead of
+/// wrapping around. Cells are addressed by signed `(row, column)`
+/// coordinates.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparseUniverse {
+    cells: HashSet<(i64, i64)>,
+    rule: Rule,
+    generation: u64,
+}
+
+impl SparseUniverse {
+    /// Creates a new empty SparseUniverse following Conway's Game of Life
+    pub fn new() -> SparseUniverse {
+        Self::default()
+    }
+
+    /// Creates a new empty SparseUniverse following the given rule
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The rule used to update the universe
+    ///
+    /// # Panics
+    ///
+    /// An unbounded plane cannot hold the infinitely many cells born from
+    /// empty space, so rules with `B0` are not supported. Only live cells
+    /// are stored, so neither are Generations rules, and only their 8
+    /// surrounding cells are visited, so neither are Larger than Life rules.
+    pub fn with_rule(rule: Rule) -> SparseUniverse {
+        assert!(
+            !rule.is_birth(0),
+            "SparseUniverse does not support B0 rules"
+        );
+        assert!(
+            rule.states() == 2,
+            "SparseUniverse does not support Generations rules"
+        );
+        assert!(
+            rule.neighborhood().range() == 1,
+            "SparseUniverse does not support Larger than Life rules"
+        );
+
+        SparseUniverse {
+            rule,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new SparseUniverse from the live cells of a universe
+    ///
+    /// The top left cell of the universe becomes the cell at `(0, 0)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `universe` - The universe to copy, whose rule is used as well
+    ///
+    /// # Panics
+    ///
+    /// Panics if the universe's rule is not supported, see
+    /// [`SparseUniverse::with_rule`] and [`SparseUniverse::try_from_universe`]
+    /// for a fallible version.
+    pub fn from_universe(universe: &Universe) -> SparseUniverse {
+        let mut sparse = Self::with_rule(universe.rule());
+        let width = universe.width() as usize;
+        for index in universe.get_cells().ones() {
+            sparse
+                .cells
+                .insert(((index / width) as i64, (index % width) as i64));
+        }
+        sparse
+    }
+
+    /// Creates a new SparseUniverse from the live cells of a universe, if
+    /// its rule is supported
+    ///
+    /// # Arguments
+    ///
+    /// * `universe` - The universe to copy, whose rule is used as well
+    ///
+    /// # Returns
+    ///
+    /// An [`LifeError::UnsupportedRule`] error for rules that
+    /// [`SparseUniverse::supports`] rejects
+    pub fn try_from_universe(universe: &Universe) -> Result<SparseUniverse, LifeError> {
+        if !Self::supports(&universe.rule()) {
+            return Err(LifeError::UnsupportedRule(universe.rule()));
+        }
+        Ok(Self::from_universe(universe))
+    }
+
+    /// Returns whether a SparseUniverse can follow a rule
+    ///
+    /// Rules with births on zero neighbors, Generations rules and Larger
+    /// than Life rules are not supported.
+    pub fn supports(rule: &Rule) -> bool {
+        !rule.is_birth(0) && rule.states() == 2 && rule.neighborhood().range() == 1
+    }
+
+    /// Updates the universe's cells for a single tick
+    ///
+    /// Only the live cells and their neighbors are visited, so the cost
+    /// depends on the population rather than the extent of the pattern.
+    pub fn tick(&mut self) {
+        // The live neighbors of each cell next to a live cell, as the bits
+        // of `Rule::next_state_for_neighbors`
+        let mut live_neighbors: HashMap<(i64, i64), u8> =
+            HashMap::with_capacity(self.cells.len() * 8);
+        for &(row, column) in &self.cells {
+            for (bit, (delta_row, delta_col)) in NEIGHBOR_OFFSETS.into_iter().enumerate() {
+                *live_neighbors
+                    .entry((row - delta_row, column - delta_col))
+                    .or_insert(0) |= 1 << bit;
+            }
+        }
+
+        let mut next: HashSet<(i64, i64)> = live_neighbors
+            .iter()
+            .filter(|&(cell, &neighbors)| {
+                self.rule
+                    .next_state_for_neighbors(self.cells.contains(cell), neighbors)
+            })
+            .map(|(&cell, _)| cell)
+            .collect();
+
+        // Live cells without any live neighbors are missing from the map
+        if self.rule.is_survival(0) {
+            next.extend(
+                self.cells
+                    .iter()
+                    .filter(|cell| !live_neighbors.contains_key(cell)),
+            );
+        }
+
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    /// Sets every cell in the universe to be dead
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Returns the rule used to update the universe
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Sets the rule used to update the universe from a rulestring
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - A rulestring such as `B3/S23`, `B36/S23` or `23/3`
+    ///
+    /// # Returns
+    ///
+    /// An error if the rulestring is invalid, has `B0` or is a Generations
+    /// or Larger than Life rule, which are not supported
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), LifeError> {
+        let rule = Rule::parse(rule)?;
+        if !Self::supports(&rule) {
+            return Err(LifeError::UnsupportedRule(rule));
+        }
+        self.rule = rule;
+        Ok(())
+    }
+
+    /// Set a cell to be alive or dead at the given coordinates
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate
+    /// * `column` - The column coordinate
+    /// * `alive` - Whether the cell should be alive or dead
+    pub fn set_cell(&mut self, row: i64, column: i64, alive: bool) {
+        if alive {
+            self.cells.insert((row, column));
+        } else {
+            self.cells.remove(&(row, column));
+        }
+    }
+
+    /// Set cells to be alive by passing the row and column of each cell
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - (row, col) coordinates for cells to set alive
+    pub fn set_cells(&mut self, cells: &[(i64, i64)]) {
+        self.cells.extend(cells.iter().cloned());
+    }
+
+    /// Check if a cell is alive at the given coordinates
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate
+    /// * `column` - The column coordinate
+    pub fn is_cell_alive(&self, row: i64, column: i64) -> bool {
+        self.cells.contains(&(row, column))
+    }
+
+    /// Toggles the state of a single cell
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate of the cell to toggle
+    /// * `column` - The column coordinate of the cell to toggle
+    pub fn toggle_cell(&mut self, row: i64, column: i64) {
+        if !self.cells.remove(&(row, column)) {
+            self.cells.insert((row, column));
+        }
+    }
+
+    /// Returns an iterator over the (row, col) coordinates of every live cell
+    pub fn live_cells(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.cells.iter().cloned()
+    }
+
+    /// Returns the smallest rectangle containing every live cell
+    ///
+    /// # Returns
+    ///
+    /// The `(top, left, bottom, right)` coordinates of the rectangle, with
+    /// inclusive bounds, or `None` if there are no live cells
+    pub fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        let mut cells = self.cells.iter();
+        let &(row, column) = cells.next()?;
+        Some(cells.fold(
+            (row, column, row, column),
+            |(top, left, bottom, right), &(row, column)| {
+                (
+                    top.min(row),
+                    left.min(column),
+                    bottom.max(row),
+                    right.max(column),
+                )
+            },
+        ))
+    }
+
+    /// Copies a rectangular region of the plane into a new universe
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row of the region's top left cell
+    /// * `column` - The column of the region's top left cell
+    /// * `width` - The width of the region
+    /// * `height` - The height of the region
+    ///
+    /// # Panics
+    ///
+    /// Panics if the universe would have more than 2^30 cells, see
+    /// [`SparseUniverse::try_to_universe`] for a fallible version.
+    pub fn to_universe(&self, row: i64, column: i64, width: u32, height: u32) -> Universe {
+        self.try_to_universe(row, column, width, height)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Copies a rectangular region of the plane into a new universe, if it
+    /// is not too large
+    ///
+    /// See [`SparseUniverse::to_universe`] for the arguments.
+    ///
+    /// # Returns
+    ///
+    /// An error if the universe would have more than 2^30 cells
+    pub fn try_to_universe(
+        &self,
+        row: i64,
+        column: i64,
+        width: u32,
+        height: u32,
+    ) -> Result<Universe, LifeError> {
+        let mut universe = Universe::try_with_rule(width, height, self.rule)?;
+        for &(cell_row, cell_col) in &self.cells {
+            let (region_row, region_col) = (cell_row - row, cell_col - column);
+            if (0..height as i64).contains(&region_row) && (0..width as i64).contains(&region_col) {
+                universe.set_cell(region_row as u32, region_col as u32, true);
+            }
+        }
+        Ok(universe)
+    }
+
+    /// Draws a blinker pattern centered on the specified cell
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate of the pattern center
+    /// * `column` - The column coordinate of the pattern center
+    /// * `orientation` - The rotation or reflection of the horizontal
+    ///   blinker, such as [`Transform::Rotate90`] for a vertical one
+    /// * `phase` - The number of generations to advance the blinker
+    ///
+    /// # Panics
+    ///
+    /// Panics if `phase` is more than 1024 generations, see
+    /// [`SparseUniverse::try_draw_blinker`] for a fallible version.
+    pub fn draw_blinker(&mut self, row: i64, column: i64, orientation: Transform, phase: u32) {
+        self.try_draw_blinker(row, column, orientation, phase)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Draws a blinker pattern centered on the specified cell, if `phase`
+    /// is at most 1024 generations
+    ///
+    /// See [`SparseUniverse::draw_blinker`] for the arguments.
+    pub fn try_draw_blinker(
+        &mut self,
+        row: i64,
+        column: i64,
+        orientation: Transform,
+        phase: u32,
+    ) -> Result<(), LifeError> {
+        self.place_offsets(
+            row,
+            column,
+            &patterns::BLINKER_HORIZONTAL,
+            orientation,
+            phase,
+            false,
+        )
+    }
+
+    /// Draws a glider pattern centered on the specified cell, clearing the
+    /// rest of the 3 by 3 square around it
+    ///
+    /// The glider heads south-east before it is oriented, like the one of
+    /// [`Universe::draw_glider`].
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate of the pattern center
+    /// * `column` - The column coordinate of the pattern center
+    /// * `orientation` - The rotation or reflection of the glider
+    /// * `phase` - The number of generations to advance the glider
+    ///
+    /// # Panics
+    ///
+    /// Panics if `phase` is more than 1024 generations, see
+    /// [`SparseUniverse::try_draw_glider`] for a fallible version.
+    pub fn draw_glider(&mut self, row: i64, column: i64, orientation: Transform, phase: u32) {
+        self.try_draw_glider(row, column, orientation, phase)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Draws a glider pattern centered on the specified cell, if `phase` is
+    /// at most 1024 generations
+    ///
+    /// See [`SparseUniverse::draw_glider`] for the arguments.
+    pub fn try_draw_glider(
+        &mut self,
+        row: i64,
+        column: i64,
+        orientation: Transform,
+        phase: u32,
+    ) -> Result<(), LifeError> {
+        self.place_offsets(row, column, &patterns::GLIDER, orientation, phase, true)
+    }
+
+    /// Draws a pulsar pattern centered on the specified cell
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate of the pattern center
+    /// * `column` - The column coordinate of the pattern center
+    /// * `orientation` - The rotation or reflection of the pulsar
+    /// * `phase` - The number of generations to advance the pulsar
+    ///
+    /// # Panics
+    ///
+    /// Panics if `phase` is more than 1024 generations, see
+    /// [`SparseUniverse::try_draw_pulsar`] for a fallible version.
+    pub fn draw_pulsar(&mut self, row: i64, column: i64, orientation: Transform, phase: u32) {
+        self.try_draw_pulsar(row, column, orientation, phase)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Draws a pulsar pattern centered on the specified cell, if `phase` is
+    /// at most 1024 generations
+    ///
+    /// See [`SparseUniverse::draw_pulsar`] for the arguments.
+    pub fn try_draw_pulsar(
+        &mut self,
+        row: i64,
+        column: i64,
+        orientation: Transform,
+        phase: u32,
+    ) -> Result<(), LifeError> {
+        self.place_offsets(row, column, &patterns::pulsar(), orientation, phase, false)
+    }
+
+    /// Places a built-in pattern, given as the offsets of its live cells
+    /// from its center, with the center of the oriented pattern on a cell
+    ///
+    /// The oriented pattern is advanced by `phase` generations on an empty
+    /// plane under the universe's rule before its live cells are drawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row coordinate of the pattern center
+    /// * `column` - The column coordinate of the pattern center
+    /// * `offsets` - (row, col) offsets from the center of the live cells
+    /// * `orientation` - The rotation or reflection of the pattern
+    /// * `phase` - The number of generations to advance the pattern
+    /// * `clear` - Whether to kill the other cells of the advanced pattern's
+    ///   bounding box
+    fn place_offsets(
+        &mut self,
+        row: i64,
+        column: i64,
+        offsets: &[(i64, i64)],
+        orientation: Transform,
+        phase: u32,
+        clear: bool,
+    ) -> Result<(), LifeError> {
+        if phase > pattern::MAX_PHASE {
+            return Err(LifeError::PhaseTooLarge {
+                phase,
+                max: pattern::MAX_PHASE,
+            });
+        }
+        let (pattern, (center_row, center_col)) = Pattern::from_offsets(offsets);
+        let (center_row, center_col) =
+            orientation.apply(center_row, center_col, pattern.width(), pattern.height());
+        let pattern = pattern.transformed(orientation);
+
+        let mut scratch = SparseUniverse {
+            rule: self.rule,
+            ..Self::default()
+        };
+        for pattern_row in 0..pattern.height() {
+            for pattern_col in 0..pattern.width() {
+                if pattern.cell_state(pattern_row, pattern_col) == 1 {
+                    scratch.cells.insert((
+                        pattern_row as i64 - center_row as i64,
+                        pattern_col as i64 - center_col as i64,
+                    ));
+                }
+            }
+        }
+        scratch.step(phase as u64);
+
+        if let (true, Some((top, left, bottom, right))) = (clear, scratch.bounding_box()) {
+            self.cells.retain(|&(cell_row, cell_col)| {
+                !((row + top..=row + bottom).contains(&cell_row)
+                    && (column + left..=column + right).contains(&cell_col))
+            });
+        }
+        let cells: Vec<_> = scratch.cells.into_iter().collect();
+        self.draw_cells(row, column, &cells);
+        Ok(())
+    }
+
+    /// Sets cells alive at offsets from a provided origin
+    fn draw_cells(&mut self, row: i64, column: i64, offsets: &[(i64, i64)]) {
+        for &(delta_row, delta_col) in offsets {
+            self.cells.insert((row + delta_row, column + delta_col));
+        }
+    }
+}
+
+impl Simulation for SparseUniverse {
+    /// Advances the universe by calling [`SparseUniverse::tick`] once per generation
+    fn step(&mut self, generations: u64) {
+        for _ in 0..generations {
+            self.tick();
+        }
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn population(&self) -> u64 {
+        self.cells.len() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Topology;
+
+    #[test]
+    fn test_glider_flies_forever() {
+        let mut universe = SparseUniverse::new();
+        universe.draw_glider(0, 0, Transform::Identity, 0);
+
+        universe.step(400);
+
+        // The glider heads south east by one cell every four generations
+        assert_eq!(universe.population(), 5);
+        assert_eq!(universe.bounding_box(), Some((99, 99, 101, 101)));
+    }
+
+    #[test]
+    fn test_blinker_oscillates() {
+        let mut universe = SparseUniverse::new();
+        universe.draw_blinker(-5, -5, Transform::Identity, 0);
+
+        universe.tick();
+        assert!(universe.is_cell_alive(-6, -5));
+        assert!(universe.is_cell_alive(-4, -5));
+        assert!(!universe.is_cell_alive(-5, -6));
+
+        universe.tick();
+        assert_eq!(universe.bounding_box(), Some((-5, -6, -5, -4)));
+    }
+
+    #[test]
+    fn test_matches_bounded_universe() {
+        for rule in ["B3/S23", "B2-a/S12", "B2/S34H", "B3/S013V"] {
+            let mut universe = Universe::with_rule(64, 64, Rule::parse(rule).unwrap());
+            universe.set_topology(Topology::Plane);
+            universe.draw_pulsar(32, 32, Transform::Identity, 0);
+            universe.draw_glider(10, 10, Transform::Identity, 0);
+            universe.draw_blinker(50, 20, Transform::Rotate90, 0);
+
+            let mut sparse = SparseUniverse::from_universe(&universe);
+            for _ in 0..12 {
+                universe.tick();
+                sparse.tick();
+                assert_eq!(
+                    sparse.to_universe(0, 0, 64, 64).get_cells(),
+                    universe.get_cells()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_draw_in_orientation_and_phase() {
+        let transforms = [
+            Transform::Identity,
+            Transform::Rotate90,
+            Transform::Rotate180,
+            Transform::Rotate270,
+            Transform::FlipHorizontal,
+            Transform::FlipVertical,
+            Transform::Transpose,
+            Transform::AntiTranspose,
+        ];
+        for orientation in transforms {
+            for phase in 0..4 {
+                let mut universe = Universe::new_empty(40, 40);
+                universe.set_topology(Topology::Plane);
+                universe.draw_pulsar(20, 20, orientation, phase);
+                universe.draw_glider(5, 5, orientation, phase);
+                universe.draw_blinker(5, 34, orientation, phase);
+
+                let mut sparse = SparseUniverse::new();
+                sparse.draw_pulsar(0, 0, orientation, phase);
+                sparse.draw_glider(-15, -15, orientation, phase);
+                sparse.draw_blinker(-15, 14, orientation, phase);
+                assert_eq!(
+                    sparse.to_universe(-20, -20, 40, 40).get_cells(),
+                    universe.get_cells()
+                );
+            }
+        }
+
+        let mut sparse = SparseUniverse::new();
+        assert_eq!(
+            sparse.try_draw_glider(0, 0, Transform::Identity, 1025),
+            Err(LifeError::PhaseTooLarge {
+                phase: 1025,
+                max: 1024
+            })
+        );
+        assert_eq!(sparse.population(), 0);
+    }
+
+    #[test]
+    fn test_set_and_toggle_cells() {
+        let mut universe = SparseUniverse::new();
+        universe.set_cells(&[(-3, 7), (1_000_000, -1_000_000)]);
+        universe.toggle_cell(-3, 7);
+        universe.toggle_cell(2, 2);
+        universe.set_cell(1_000_000, -1_000_000, false);
+
+        assert!(!universe.is_cell_alive(-3, 7));
+        assert!(universe.is_cell_alive(2, 2));
+        assert_eq!(universe.live_cells().collect::<Vec<_>>(), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn test_set_rule_rejects_b0() {
+        let mut universe = SparseUniverse::new();
+        assert_eq!(
+            universe.set_rule("B0/S8"),
+            Err(LifeError::UnsupportedRule(Rule::parse("B0/S8").unwrap()))
+        );
+        universe.set_rule("B36/S23").unwrap();
+        assert_eq!(universe.rule().to_string(), "B36/S23");
+    }
+
+    #[test]
+    fn test_try_from_universe_rejects_unsupported_rules() {
+        let mut universe = Universe::new_empty(8, 8);
+        universe.draw_glider(4, 4, Transform::Identity, 0);
+        let sparse = SparseUniverse::try_from_universe(&universe).unwrap();
+        assert_eq!(sparse.population(), 5);
+
+        let rule = Rule::parse("R2,C0,M0,S2..3,B3..3,NM").unwrap();
+        assert_eq!(
+            SparseUniverse::try_from_universe(&Universe::with_rule(8, 8, rule)),
+            Err(LifeError::UnsupportedRule(rule))
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+
+/// The way the edges of a universe are joined together
+///
+/// The topology decides which cell a coordinate outside of the universe
+/// refers to, both when counting neighbors and when drawing patterns that
+/// extend past an edge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Topology {
+    /// Both pairs of opposite edges are joined, so the grid wraps in every direction
+    #[default]
+    Torus,
+    /// No edges are joined; every cell outside of the universe is dead
+    Plane,
+    /// The left and right edges are joined, so the grid wraps horizontally only
+    CylinderHorizontal,
+    /// The top and bottom edges are joined, so the grid wraps vertically only
+    CylinderVertical,
+    /// The left and right edges are joined, while the top and bottom edges
+    /// are joined with a half twist that mirrors the column
+    KleinBottle,
+    /// Both pairs of opposite edges are joined with a half twist, which mirrors
+    /// the column when wrapping vertically and the row when wrapping horizontally
+    CrossSurface,
+    /// Like a torus, but moving off the bottom edge re-enters at the top
+    /// shifted by the given number of columns to the right
+    ShiftedTorus(i32),
+}
+
+impl Topology {
+    /// Maps a possibly out of range coordinate onto a cell of the universe
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the universe
+    /// * `height` - The height of the universe
+    /// * `row` - The row coordinate, which may lie outside of the universe
+    /// * `column` - The column coordinate, which may lie outside of the universe
+    ///
+    /// # Returns
+    ///
+    /// The `(row, column)` of the cell the coordinate refers to, or `None`
+    /// if the coordinate lies past an edge that is not joined.
+    pub fn wrap(&self, width: u32, height: u32, row: i64, column: i64) -> Option<(u32, u32)> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let (width, height) = (width as i64, height as i64);
+        let inside = |value: i64, size: i64| (0..size).contains(&value);
+        let mirror = |value: i64, size: i64| size - 1 - value;
+
+        let (row, column) = match *self {
+            Topology::Torus => (row.rem_euclid(height), column.rem_euclid(width)),
+            Topology::Plane => (row, column),
+            Topology::CylinderHorizontal => (row, column.rem_euclid(width)),
+            Topology::CylinderVertical => (row.rem_euclid(height), column),
+            Topology::KleinBottle => {
+                let twists = row.div_euclid(height);
+                let column = column.rem_euclid(width);
+                let column = if twists % 2 == 0 {
+                    column
+                } else {
+                    mirror(column, width)
+                };
+                (row.rem_euclid(height), column)
+            }
+            Topology::CrossSurface => {
+                let row_twists = row.div_euclid(height);
+                let column_twists = column.div_euclid(width);
+                let (row, column) = (row.rem_euclid(height), column.rem_euclid(width));
+                let column = if row_twists % 2 == 0 {
+                    column
+                } else {
+                    mirror(column, width)
+                };
+                let row = if column_twists % 2 == 0 {
+                    row
+                } else {
+                    mirror(row, height)
+                };
+                (row, column)
+            }
+            Topology::ShiftedTorus(shift) => {
+                let wraps = row.div_euclid(height);
+                let column = column + wraps * shift as i64;
+                (row.rem_euclid(height), column.rem_euclid(width))
+            }
+        };
+
+        if inside(row, height) && inside(column, width) {
+            Some((row as u32, column as u32))
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether the rows and the columns wrap around without a twist
+    /// or shift, so that moving a whole pattern past those edges only
+    /// changes its coordinates modulo the height or width
+    ///
+    /// # Returns
+    ///
+    /// Whether rows wrap from the bottom edge to the top, and whether
+    /// columns wrap from the right edge to the left
+    pub(crate) fn plain_wraps(&self) -> (bool, bool) {
+        match *self {
+            Topology::Torus => (true, true),
+            Topology::Plane | Topology::CrossSurface => (false, false),
+            Topology::CylinderHorizontal | Topology::KleinBottle | Topology::ShiftedTorus(_) => {
+                (false, true)
+            }
+            Topology::CylinderVertical => (true, false),
+        }
+    }
+}
+
+impl FromStr for Topology {
+    type Err = TopologyParseError;
+
+    /// Parses a topology name such as `torus`, `plane` or `shifted-torus:3`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        let topology = match name.as_str() {
+            "torus" => Topology::Torus,
+            "plane" => Topology::Plane,
+            "cylinder-horizontal" => Topology::CylinderHorizontal,
+            "cylinder-vertical" => Topology::CylinderVertical,
+            "klein-bottle" => Topology::KleinBottle,
+            "cross-surface" => Topology::CrossSurface,
+            _ => match name.strip_prefix("shifted-torus:") {
+                Some(shift) => Topology::ShiftedTorus(
+                    shift
+                        .parse()
+                        .map_err(|_| TopologyParseError(s.to_string()))?,
+                ),
+                None => return Err(TopologyParseError(s.to_string())),
+            },
+        };
+        Ok(topology)
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Topology::Torus => write!(f, "torus"),
+            Topology::Plane => write!(f, "plane"),
+            Topology::CylinderHorizontal => write!(f, "cylinder-horizontal"),
+            Topology::CylinderVertical => write!(f, "cylinder-vertical"),
+            Topology::KleinBottle => write!(f, "klein-bottle"),
+            Topology::CrossSurface => write!(f, "cross-surface"),
+            Topology::ShiftedTorus(shift) => write!(f, "shifted-torus:{}", shift),
+        }
+    }
+}
+
+/// An error returned when a topology name cannot be parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TopologyParseError(String);
+
+impl fmt::Display for TopologyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown topology '{}'", self.0)
+    }
+}
+
+impl std::error::Error for TopologyParseError {}
+
+/// Serializes the topology as its name
+#[cfg(feature = "serde")]
+impl serde::Serialize for Topology {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Topology {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<TopologyParseError> for JsValue {
+    fn from(error: TopologyParseError) -> Self {
+        js_sys::Error::new(&error.to_string()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inside_coordinates_are_unchanged() {
+        let topologies = [
+            Topology::Torus,
+            Topology::Plane,
+            Topology::CylinderHorizontal,
+            Topology::CylinderVertical,
+            Topology::KleinBottle,
+            Topology::CrossSurface,
+            Topology::ShiftedTorus(2),
+        ];
+        for topology in topologies {
+            assert_eq!(topology.wrap(5, 4, 3, 2), Some((3, 2)));
+        }
+    }
+
+    #[test]
+    fn test_torus_and_plane() {
+        assert_eq!(Topology::Torus.wrap(5, 4, -1, 5), Some((3, 0)));
+        assert_eq!(Topology::Plane.wrap(5, 4, -1, 0), None);
+        assert_eq!(Topology::Plane.wrap(5, 4, 0, 5), None);
+    }
+
+    #[test]
+    fn test_cylinders() {
+        assert_eq!(Topology::CylinderHorizontal.wrap(5, 4, 0, -1), Some((0, 4)));
+        assert_eq!(Topology::CylinderHorizontal.wrap(5, 4, 4, 0), None);
+        assert_eq!(Topology::CylinderVertical.wrap(5, 4, 4, 0), Some((0, 0)));
+        assert_eq!(Topology::CylinderVertical.wrap(5, 4, 0, -1), None);
+    }
+
+    #[test]
+    fn test_twisted_surfaces() {
+        // Wrapping vertically mirrors the column
+        assert_eq!(Topology::KleinBottle.wrap(5, 4, -1, 1), Some((3, 3)));
+        assert_eq!(Topology::KleinBottle.wrap(5, 4, 4, 0), Some((0, 4)));
+        // Wrapping horizontally does not
+        assert_eq!(Topology::KleinBottle.wrap(5, 4, 1, 5), Some((1, 0)));
+
+        assert_eq!(Topology::CrossSurface.wrap(5, 4, -1, 1), Some((3, 3)));
+        assert_eq!(Topology::CrossSurface.wrap(5, 4, 1, 5), Some((2, 0)));
+    }
+
+    #[test]
+    fn test_shifted_torus() {
+        assert_eq!(Topology::ShiftedTorus(2).wrap(5, 4, 4, 1), Some((0, 3)));
+        assert_eq!(Topology::ShiftedTorus(2).wrap(5, 4, -1, 3), Some((3, 1)));
+        assert_eq!(Topology::ShiftedTorus(2).wrap(5, 4, 0, 5), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        for name in ["torus", "plane", "klein-bottle", "shifted-torus:-3"] {
+            let topology: Topology = name.parse().unwrap();
+            assert_eq!(topology.to_string(), name);
+        }
+        assert!("sphere".parse::<Topology>().is_err());
+        assert!("shifted-torus:x".parse::<Topology>().is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Tests of the `life` command-line tool
+
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+const GLIDER: &str = "x = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n";
+
+/// Runs the tool with a pattern on standard input
+fn life(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_life"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: Output) -> String {
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn run_steps_glider() {
+    // A glider has the same shape every 4 generations
+    let output = stdout(life(&["run", "-", "--generations", "400"], GLIDER));
+    assert_eq!(output, "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+
+    let output = stdout(life(
+        &["run", "-", "-g", "2", "-r", "B2/S/C3", "-f", "plaintext"],
+        "OO\n",
+    ));
+    assert_eq!(output, ".OO.\n....\nO..O\n....\n.OO.\n");
+}
+
+#[test]
+fn run_rejects_patterns_growing_past_the_padding() {
+    let args = ["run", "-", "-g", "200", "-r", "B2/S/C3"];
+    let output = stdout(life(&args, "OO\n"));
+    assert!(output.starts_with("x = 202, y = 401, rule = B2/S/C3\n"));
+
+    let output = life(&[&args[..], &["--padding", "10"]].concat(), "OO\n");
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("grew past the padding of 10 cells"));
+}
+
+#[test]
+fn run_rejects_results_too_large_for_a_universe() {
+    // Two gliders flying apart in opposite directions
+    let gliders = "OOO\nO..\n.O.\n...\n....O.\n.....O\n...OOO\n";
+    let output = life(&["run", "-", "-g", "100000000"], gliders);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("use -f macrocell"));
+
+    let output = stdout(life(
+        &["run", "-", "-g", "100000000", "-f", "macrocell"],
+        gliders,
+    ));
+    assert!(output.starts_with("[M2]"));
+}
+
+#[test]
+fn convert_between_formats() {
+    let life106 = stdout(life(&["convert", "-", "-f", "life106"], GLIDER));
+    assert_eq!(life106, "#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2\n");
+
+    let macrocell = stdout(life(&["convert", "-", "-f", "macrocell"], &life106));
+    let plaintext = stdout(life(&["convert", "-", "-f", "plaintext"], &macrocell));
+    assert_eq!(plaintext, ".O.\n..O\nOOO\n");
+}
+
+#[test]
+fn info_reports_cycle() {
+    let output = stdout(life(&["info", "-"], GLIDER));
+    assert!(output.contains("population: 5\n"));
+    assert!(output.contains("bounding box: 3x3 at row 0, column 0\n"));
+    assert!(output.contains("spaceship with period 4"));
+}
+
+#[test]
+fn render_formats() {
+    assert_eq!(stdout(life(&["render", "-"], "OO\n")), "◼◼\n");
+    assert!(stdout(life(&["render", "-", "-f", "svg"], "OO\n")).starts_with("<svg"));
+
+    let output = life(&["render", "-", "-f", "png"], "OO\n");
+    assert!(output.status.success());
+    assert!(output.stdout.starts_with(b"\x89PNG"));
+}
+
+#[test]
+fn invalid_rule_fails() {
+    let output = life(&["run", "-", "-g", "1", "-r", "B9"], GLIDER);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("life: "));
+}